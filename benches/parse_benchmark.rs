@@ -9,10 +9,8 @@ const SOCKET_OFF: &'static str = include_str!("../resources/socket.off");
 
 pub fn criterion_benchmark(c: &mut Criterion) {
 	c.bench_function("parse wiki - onoff", |b| {
-		let opts = onoff::parse::ParserOptions {
-			color_format: colorformat::RgbU8,
-			limits: Default::default(),
-		};
+		let opts =
+			onoff::parse::ParserOptions::with_color_format(colorformat::RgbU8);
 
 		b.iter(|| {
 			(black_box(
@@ -29,10 +27,8 @@ pub fn criterion_benchmark(c: &mut Criterion) {
 	});
 
 	c.bench_function("parse prinston - onoff", |b| {
-		let opts = onoff::parse::ParserOptions {
-			color_format: colorformat::RgbU8,
-			limits: Default::default(),
-		};
+		let opts =
+			onoff::parse::ParserOptions::with_color_format(colorformat::RgbU8);
 
 		b.iter(|| {
 			(black_box(
@@ -49,10 +45,8 @@ pub fn criterion_benchmark(c: &mut Criterion) {
 	});
 
 	c.bench_function("parse socket - onoff", |b| {
-		let opts = onoff::parse::ParserOptions {
-			color_format: colorformat::RgbU8,
-			limits: Default::default(),
-		};
+		let opts =
+			onoff::parse::ParserOptions::with_color_format(colorformat::RgbU8);
 
 		b.iter(|| {
 			(black_box(
@@ -69,10 +63,8 @@ pub fn criterion_benchmark(c: &mut Criterion) {
 	});
 
 	c.bench_function("parse wiki - onoff @ ANY", |b| {
-		let opts = onoff::parse::ParserOptions {
-			color_format: colorformat::Any,
-			limits: Default::default(),
-		};
+		let opts =
+			onoff::parse::ParserOptions::with_color_format(colorformat::Any);
 
 		b.iter(|| {
 			(black_box(
@@ -81,10 +73,8 @@ pub fn criterion_benchmark(c: &mut Criterion) {
 		})
 	});
 	c.bench_function("parse prinston - onoff @ ANY", |b| {
-		let opts = onoff::parse::ParserOptions {
-			color_format: colorformat::Any,
-			limits: Default::default(),
-		};
+		let opts =
+			onoff::parse::ParserOptions::with_color_format(colorformat::Any);
 
 		b.iter(|| {
 			(black_box(
@@ -93,10 +83,8 @@ pub fn criterion_benchmark(c: &mut Criterion) {
 		})
 	});
 	c.bench_function("parse socket - onoff @ ANY", |b| {
-		let opts = onoff::parse::ParserOptions {
-			color_format: colorformat::None,
-			limits: Default::default(),
-		};
+		let opts =
+			onoff::parse::ParserOptions::with_color_format(colorformat::None);
 
 		b.iter(|| {
 			(black_box(
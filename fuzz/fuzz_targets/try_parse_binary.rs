@@ -0,0 +1,8 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+
+use onoff::parse::binary::BinaryOffParser;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = BinaryOffParser::new(data).try_parse();
+});
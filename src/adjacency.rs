@@ -0,0 +1,175 @@
+use std::collections::BTreeMap;
+
+use crate::document::{Checked, Document};
+use crate::vertex::VertexRef;
+
+/// Precomputed neighbourhood of the vertices, edges and faces of a
+/// document (see [`Document::adjacency`]).
+///
+/// Edges are undirected and given by the consecutive vertices of the faces.
+/// Faces are referenced by their index in the document. All lists are
+/// sorted and free of duplicates.
+///
+/// Unlike [`HalfEdgeMesh`](crate::halfedge::HalfEdgeMesh) any document is
+/// supported, including non-manifold ones. The adjacency is not updated
+/// when the document is edited.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Adjacency {
+	vertex_faces: Vec<Vec<usize>>,
+	vertex_neighbours: Vec<Vec<VertexRef>>,
+	face_neighbours: Vec<Vec<usize>>,
+	/// Faces of each edge by the indices of its vertices (smaller first)
+	edge_faces: BTreeMap<(usize, usize), Vec<usize>>,
+}
+
+impl Adjacency {
+	pub fn new(document: &Document<Checked>) -> Self {
+		let vertex_count = document.vertices.len();
+		let mut vertex_faces = vec![Vec::new(); vertex_count];
+		let mut vertex_neighbours = vec![Vec::new(); vertex_count];
+		let mut edge_faces = BTreeMap::<_, Vec<_>>::new();
+
+		for (face, face_ref) in document.face_refs.iter().enumerate() {
+			for vertex_ref in &face_ref.vertex_refs {
+				vertex_faces[vertex_ref.0].push(face);
+			}
+
+			for (a, b) in face_ref.undirected_edges() {
+				vertex_neighbours[a].push(VertexRef(b));
+				vertex_neighbours[b].push(VertexRef(a));
+				edge_faces.entry((a, b)).or_default().push(face);
+			}
+		}
+
+		let mut face_neighbours =
+			vec![Vec::<usize>::new(); document.face_refs.len()];
+
+		for faces in edge_faces.values_mut() {
+			faces.dedup();
+
+			for &face in faces.iter() {
+				face_neighbours[face].extend(
+					faces.iter().filter(|&&other| other != face).copied(),
+				);
+			}
+		}
+
+		// Faces are added in order, so only duplicates have to be removed
+		for faces in &mut vertex_faces {
+			faces.dedup();
+		}
+
+		sort_all(&mut vertex_neighbours);
+		sort_all(&mut face_neighbours);
+
+		Self { vertex_faces, vertex_neighbours, face_neighbours, edge_faces }
+	}
+
+	/// Faces which contain the vertex.
+	pub fn vertex_faces(&self, vertex: VertexRef) -> &[usize] {
+		&self.vertex_faces[vertex.0]
+	}
+
+	/// Vertices which share an edge with the vertex.
+	pub fn vertex_neighbours(&self, vertex: VertexRef) -> &[VertexRef] {
+		&self.vertex_neighbours[vertex.0]
+	}
+
+	/// Faces which share at least one edge with the face.
+	pub fn face_neighbours(&self, face: usize) -> &[usize] {
+		&self.face_neighbours[face]
+	}
+
+	/// Faces on the edge between `a` and `b` (in any order). Empty if there
+	/// is no such edge.
+	pub fn edge_faces(&self, a: VertexRef, b: VertexRef) -> &[usize] {
+		self.edge_faces
+			.get(&(a.0.min(b.0), a.0.max(b.0)))
+			.map_or(&[], Vec::as_slice)
+	}
+
+	/// All edges (with the vertex of the smaller index first) together with
+	/// their faces.
+	pub fn edges(
+		&self,
+	) -> impl Iterator<Item = ((VertexRef, VertexRef), &[usize])> + '_ {
+		self.edge_faces
+			.iter()
+			.map(|(&(a, b), faces)| ((VertexRef(a), VertexRef(b)), &faces[..]))
+	}
+
+	pub fn edge_count(&self) -> usize {
+		self.edge_faces.len()
+	}
+}
+
+fn sort_all<T: Ord>(lists: &mut [Vec<T>]) {
+	for list in lists {
+		list.sort_unstable();
+		list.dedup();
+	}
+}
+
+impl Document<Checked> {
+	/// Computes the adjacency of the vertices and faces (see [`Adjacency`]).
+	pub fn adjacency(&self) -> Adjacency {
+		Adjacency::new(self)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::parse::OffParser;
+
+	#[test]
+	fn cube() {
+		let content = include_str!("../resources/wiki.off");
+		let document =
+			OffParser::new(&content).try_parse().unwrap().validate().unwrap();
+		let adjacency = document.adjacency();
+
+		assert_eq!(adjacency.edge_count(), 12);
+		assert!(adjacency.edges().all(|(_, faces)| faces.len() == 2));
+
+		for vertex in 0..8 {
+			assert_eq!(adjacency.vertex_faces(VertexRef(vertex)).len(), 3);
+			assert_eq!(
+				adjacency.vertex_neighbours(VertexRef(vertex)).len(),
+				3
+			);
+		}
+
+		for face in 0..6 {
+			let neighbours = adjacency.face_neighbours(face);
+
+			assert_eq!(neighbours.len(), 4);
+			assert!(!neighbours.contains(&face));
+		}
+	}
+
+	#[test]
+	fn non_manifold() {
+		let content = "OFF\n6 4\n0 0 0\n1 0 0\n0 1 0\n0 -1 0\n0 0 1\n5 5 \
+		               5\n3 0 1 2\n3 1 0 3\n3 0 1 4\n3 2 2 1\n";
+		let document =
+			OffParser::new(&content).try_parse().unwrap().validate().unwrap();
+		let adjacency = document.adjacency();
+
+		assert_eq!(
+			adjacency.edge_faces(VertexRef(1), VertexRef(0)),
+			[0, 1, 2]
+		);
+		assert_eq!(adjacency.edge_faces(VertexRef(2), VertexRef(2)), []);
+		assert_eq!(adjacency.edge_faces(VertexRef(1), VertexRef(2)), [0, 3]);
+		assert_eq!(adjacency.face_neighbours(0), [1, 2, 3]);
+		assert_eq!(adjacency.face_neighbours(3), [0]);
+		assert_eq!(adjacency.vertex_faces(VertexRef(2)), [0, 3]);
+		assert_eq!(
+			adjacency.vertex_neighbours(VertexRef(0)),
+			[VertexRef(1), VertexRef(2), VertexRef(3), VertexRef(4)]
+		);
+		assert_eq!(adjacency.vertex_faces(VertexRef(5)), []);
+		assert_eq!(adjacency.vertex_neighbours(VertexRef(5)), []);
+	}
+}
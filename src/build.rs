@@ -1,26 +1,129 @@
+use std::collections::HashMap;
+use std::fmt;
 use std::marker::PhantomData;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
-use crate::color::Color;
+use crate::color::{Color, FaceColor};
 use crate::document::{Checked, Document};
 use crate::face::FaceRef;
 use crate::vertex::{Vertex, VertexRef};
 
-#[derive(Default, Debug, Clone)]
+/// How [`CheckedDocumentBuilder::finish`] merges vertices which are equal.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum VertexMerge {
+	/// Keep every vertex, even if it was added more than once.
+	#[default]
+	None,
+	/// Merge vertices with bitwise equal coordinates.
+	Exact,
+	/// Merge vertices whose coordinates each differ by at most the given
+	/// tolerance. A vertex is merged into the first vertex within tolerance,
+	/// so the result depends on the order in which vertices were added.
+	Tolerance(f32),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BuildError {
+	/// The vertex was added to another builder.
+	ForeignVertex(VertexHandle),
+}
+
+impl fmt::Display for BuildError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::ForeignVertex(handle) => write!(
+				f,
+				"Vertex `{}` belongs to another builder",
+				handle.index
+			),
+		}
+	}
+}
+
+impl std::error::Error for BuildError {}
+
+pub type Result<T, E = BuildError> = std::result::Result<T, E>;
+
+/// Vertex added to a [`CheckedDocumentBuilder`] (see
+/// [`CheckedDocumentBuilder::add_vertex`]). It can only be used with the
+/// builder which created it (and clones of that builder).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct VertexHandle {
+	builder: usize,
+	index: usize,
+}
+
+impl VertexHandle {
+	/// Index of the vertex in its builder, which is also its index in the
+	/// document unless vertices are merged (see
+	/// [`CheckedDocumentBuilder::finish_with_remap`]).
+	pub fn index(self) -> usize {
+		self.index
+	}
+}
+
+#[derive(Debug, Clone)]
 pub struct CheckedDocumentBuilder {
+	/// Identifies the handles of this builder
+	id: usize,
 	vertices: Vec<Vertex>,
 	face_refs: Vec<FaceRef>,
 	edge_count: Option<u64>,
+	merge: VertexMerge,
+}
+
+impl Default for CheckedDocumentBuilder {
+	fn default() -> Self {
+		static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+		Self {
+			id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+			vertices: Vec::new(),
+			face_refs: Vec::new(),
+			edge_count: None,
+			merge: VertexMerge::None,
+		}
+	}
 }
 
 impl CheckedDocumentBuilder {
-	#[must_use]
-	pub fn add_vertex(mut self, vertex: Vertex) -> Self {
+	/// Adds a vertex and returns a handle to it, which can be used for any
+	/// number of faces (see [`CheckedDocumentBuilder::add_face`]).
+	pub fn add_vertex(&mut self, vertex: Vertex) -> VertexHandle {
 		self.vertices.push(vertex);
-		self
+
+		VertexHandle { builder: self.id, index: self.vertices.len() - 1 }
 	}
 
-	#[must_use]
+	/// Adds a face of vertices which were added to this builder. Fails if
+	/// a handle belongs to another builder.
 	pub fn add_face(
+		&mut self,
+		vertices: Vec<VertexHandle>,
+		color: Option<FaceColor>,
+	) -> Result<()> {
+		if let Some(&handle) =
+			vertices.iter().find(|handle| handle.builder != self.id)
+		{
+			return Err(BuildError::ForeignVertex(handle));
+		}
+
+		// Handles are only created for added vertices, so they are in bounds
+		let vertex_refs = vertices
+			.into_iter()
+			.map(|handle| VertexRef(handle.index))
+			.collect();
+
+		self.face_refs.push(FaceRef::new(vertex_refs, color));
+
+		Ok(())
+	}
+
+	/// Adds a face of new vertices, which are not shared with any other
+	/// face (unless they are merged, see
+	/// [`CheckedDocumentBuilder::merge_vertices`]).
+	#[must_use]
+	pub fn with_face(
 		mut self,
 		vertices: Vec<Vertex>,
 		color: Option<Color>,
@@ -34,7 +137,8 @@ impl CheckedDocumentBuilder {
 			})
 			.collect();
 
-		self.face_refs.push(FaceRef { vertex_refs, color });
+		self.face_refs
+			.push(FaceRef::new(vertex_refs, color.map(FaceColor::Color)));
 
 		self
 	}
@@ -45,20 +149,306 @@ impl CheckedDocumentBuilder {
 		self
 	}
 
+	/// Sets how equal vertices are merged (default: [`VertexMerge::None`]).
+	/// Faces referencing merged vertices are updated accordingly.
+	///
+	/// Consecutive vertices of a face which are merged into one are only
+	/// kept once. Faces which are left with less than three vertices by this
+	/// are removed.
+	#[must_use]
+	pub fn merge_vertices(mut self, merge: VertexMerge) -> Self {
+		self.merge = merge;
+		self
+	}
+
+	/// Creates the document. If vertices are merged, the index of a handle
+	/// no longer is the index of its vertex in the document (see
+	/// [`CheckedDocumentBuilder::finish_with_remap`]).
 	#[must_use]
 	pub fn finish(self) -> Document<Checked> {
-		// TODO: minimize vertices (compare with bits?)
-		Document {
+		self.finish_with_remap().0
+	}
+
+	/// Same as [`CheckedDocumentBuilder::finish`], but also returns the
+	/// vertex of the document for each vertex of the builder (by
+	/// [`VertexHandle::index`]).
+	#[must_use]
+	pub fn finish_with_remap(mut self) -> (Document<Checked>, Vec<VertexRef>) {
+		let merged = match self.merge {
+			VertexMerge::None => None,
+			VertexMerge::Tolerance(tolerance) if tolerance > 0.0 => {
+				Some(merge_within(&self.vertices, tolerance))
+			}
+			// A tolerance of `0` is the same as comparing exactly
+			VertexMerge::Exact | VertexMerge::Tolerance(_) => {
+				Some(merge_exact(&self.vertices))
+			}
+		};
+
+		let remap = match merged {
+			Some((vertices, remap)) => {
+				self.vertices = vertices;
+
+				self.face_refs.retain_mut(|face_ref| {
+					let vertex_refs = &mut face_ref.vertex_refs;
+					let count = vertex_refs.len();
+
+					for vertex_ref in vertex_refs.iter_mut() {
+						vertex_ref.0 = remap[vertex_ref.0];
+					}
+
+					// Merged neighbours would form edges of zero length
+					vertex_refs.dedup();
+					while vertex_refs.len() > 1
+						&& vertex_refs.first() == vertex_refs.last()
+					{
+						vertex_refs.pop();
+					}
+
+					vertex_refs.len() == count || vertex_refs.len() >= 3
+				});
+
+				remap
+			}
+			None => (0..self.vertices.len()).collect(),
+		};
+
+		let document = Document {
+			header: Default::default(),
 			vertices: self.vertices,
+			coordinates: None,
+			vertex_w: None,
+			vertex_colors: None,
+			vertex_normals: None,
+			texture_coords: None,
 			face_refs: self.face_refs,
 			edge_count: self.edge_count,
 			_marker: PhantomData,
-		}
+		};
+
+		(document, remap.into_iter().map(VertexRef).collect())
 	}
 }
 
+/// Merged vertices and the new index of each of the original vertices.
+type Merged = (Vec<Vertex>, Vec<usize>);
+
+fn merge_exact(vertices: &[Vertex]) -> Merged {
+	let mut merged = Vec::new();
+	let mut indices = HashMap::new();
+
+	let remap = vertices
+		.iter()
+		.map(|vertex| {
+			let bits =
+				[vertex.x.to_bits(), vertex.y.to_bits(), vertex.z.to_bits()];
+
+			*indices.entry(bits).or_insert_with(|| {
+				merged.push(*vertex);
+				merged.len() - 1
+			})
+		})
+		.collect();
+
+	(merged, remap)
+}
+
+fn merge_within(vertices: &[Vertex], tolerance: f32) -> Merged {
+	// Vertices are sorted into cells of the size of the tolerance, so that
+	// only the neighbouring cells have to be searched
+	let cell = |vertex: &Vertex| {
+		[vertex.x, vertex.y, vertex.z].map(|c| (c / tolerance).floor() as i64)
+	};
+	let within = |a: &Vertex, b: &Vertex| {
+		(a.x - b.x).abs() <= tolerance
+			&& (a.y - b.y).abs() <= tolerance
+			&& (a.z - b.z).abs() <= tolerance
+	};
+
+	let mut merged = Vec::<Vertex>::new();
+	let mut cells = HashMap::<[i64; 3], Vec<usize>>::new();
+
+	let remap = vertices
+		.iter()
+		.map(|vertex| {
+			let [x, y, z] = cell(vertex);
+
+			let neighbours = (-1..=1).flat_map(|dx| {
+				(-1..=1).flat_map(move |dy| {
+					(-1..=1).map(move |dz| [x + dx, y + dy, z + dz])
+				})
+			});
+
+			let existing = neighbours
+				.filter_map(|key| cells.get(&key))
+				.flatten()
+				.copied()
+				.filter(|&index| within(&merged[index], vertex))
+				.min();
+
+			existing.unwrap_or_else(|| {
+				merged.push(*vertex);
+				cells.entry([x, y, z]).or_default().push(merged.len() - 1);
+				merged.len() - 1
+			})
+		})
+		.collect();
+
+	(merged, remap)
+}
+
 impl Document<Checked> {
 	pub fn build() -> CheckedDocumentBuilder {
 		CheckedDocumentBuilder::default()
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Cube built face by face, so that each corner is added three times.
+	/// The coordinates of every other face are moved by `offset`.
+	fn cube(merge: VertexMerge, offset: f32) -> Document<Checked> {
+		let corner = |i: usize, offset: f32| {
+			let c = |bit| if i & bit == 0 { 0.0 } else { 1.0 + offset };
+			Vertex::new(c(1), c(2), c(4))
+		};
+		let faces = [
+			[0, 1, 3, 2],
+			[4, 6, 7, 5],
+			[0, 4, 5, 1],
+			[2, 3, 7, 6],
+			[0, 2, 6, 4],
+			[1, 5, 7, 3],
+		];
+
+		faces
+			.iter()
+			.enumerate()
+			.fold(Document::build(), |builder, (i, face)| {
+				let offset = if i % 2 == 0 { 0.0 } else { offset };
+				let vertices =
+					face.iter().map(|&index| corner(index, offset)).collect();
+
+				builder.with_face(vertices, None)
+			})
+			.merge_vertices(merge)
+			.finish()
+	}
+
+	#[test]
+	fn merge_exact() {
+		let document = cube(VertexMerge::None, 0.0);
+		assert_eq!(document.vertices().len(), 24);
+
+		let document = cube(VertexMerge::Exact, 0.0);
+		assert_eq!(document.vertices().len(), 8);
+		assert_eq!(document.count_edges(), 12);
+
+		let corners = document.face_refs()[0]
+			.vertex_refs
+			.iter()
+			.map(|vertex_ref| document.vertices()[vertex_ref.0])
+			.collect::<Vec<_>>();
+		assert_eq!(
+			corners,
+			[
+				Vertex::new(0.0, 0.0, 0.0),
+				Vertex::new(1.0, 0.0, 0.0),
+				Vertex::new(1.0, 1.0, 0.0),
+				Vertex::new(0.0, 1.0, 0.0),
+			]
+		);
+	}
+
+	#[test]
+	fn shared_vertices() {
+		let mut builder = Document::build();
+
+		let a = builder.add_vertex(Vertex::new(0.0, 0.0, 0.0));
+		let b = builder.add_vertex(Vertex::new(1.0, 0.0, 0.0));
+		let c = builder.add_vertex(Vertex::new(0.0, 1.0, 0.0));
+		let d = builder.add_vertex(Vertex::new(1.0, 1.0, 0.0));
+
+		builder.add_face(vec![a, b, c], None).unwrap();
+		builder
+			.add_face(
+				vec![c, b, d],
+				Some(FaceColor::Color(Color::new(255, 0, 0, 255))),
+			)
+			.unwrap();
+		builder.add_face(vec![a, d, c], Some(FaceColor::Index(2))).unwrap();
+
+		let document = builder.finish();
+		assert_eq!(document.vertices().len(), 4);
+		assert_eq!(
+			document.face_refs()[1].vertex_refs(),
+			[VertexRef(2), VertexRef(1), VertexRef(3)]
+		);
+		assert_eq!(
+			document.face_refs()[2].face_color(),
+			Some(FaceColor::Index(2))
+		);
+		assert_eq!(document.count_edges(), 6);
+	}
+
+	#[test]
+	fn foreign_vertex() {
+		let mut builder = Document::build();
+		let mut other = Document::build();
+
+		let own = builder.add_vertex(Vertex::new(0.0, 0.0, 0.0));
+		let foreign = other.add_vertex(Vertex::new(1.0, 0.0, 0.0));
+		assert_eq!(own.index(), foreign.index());
+
+		assert_eq!(
+			builder.add_face(vec![own, foreign], None),
+			Err(BuildError::ForeignVertex(foreign))
+		);
+		assert!(builder.finish().face_refs().is_empty());
+	}
+
+	#[test]
+	fn merge_within_tolerance() {
+		let document = cube(VertexMerge::Exact, 1e-4);
+		assert!(document.vertices().len() > 8);
+
+		let document = cube(VertexMerge::Tolerance(1e-3), 1e-4);
+		assert_eq!(document.vertices().len(), 8);
+		assert_eq!(document.count_edges(), 12);
+
+		let document = cube(VertexMerge::Tolerance(1e-5), 1e-4);
+		assert!(document.vertices().len() > 8);
+	}
+
+	#[test]
+	fn merge_collapsed_faces() {
+		let mut builder =
+			Document::build().merge_vertices(VertexMerge::Tolerance(1e-3));
+
+		let a = builder.add_vertex(Vertex::new(0.0, 0.0, 0.0));
+		let b = builder.add_vertex(Vertex::new(1.0, 0.0, 0.0));
+		let c = builder.add_vertex(Vertex::new(1.0, 1.0, 0.0));
+		let d = builder.add_vertex(Vertex::new(1e-4, 0.0, 0.0));
+		let e = builder.add_vertex(Vertex::new(0.0, 1.0, 0.0));
+
+		// Quad whose last vertex merges with the first one
+		builder.add_face(vec![a, b, c, d], None).unwrap();
+		// Triangle with two merged vertices
+		builder.add_face(vec![a, d, e], None).unwrap();
+		builder.add_face(vec![a, c, e], None).unwrap();
+
+		let (document, remap) = builder.finish_with_remap();
+		assert_eq!(document.vertices().len(), 4);
+		assert_eq!(remap[d.index()], remap[a.index()]);
+
+		let faces = document
+			.face_refs()
+			.iter()
+			.map(|face_ref| face_ref.vertex_refs().to_vec())
+			.collect::<Vec<_>>();
+		let [a, b, c, e] = [a, b, c, e].map(|handle| remap[handle.index()]);
+		assert_eq!(faces, [vec![a, b, c], vec![a, c, e]]);
+	}
+}
@@ -60,4 +60,24 @@ impl Color {
 
 		Ok(Self::new(red, green, blue, alpha))
 	}
+
+	/// Converts all elements to floats in the range `0.0 - 1.0`.
+	///
+	/// This is the inverse of [`Color::try_from_f32`].
+	pub fn to_f32(&self) -> [f32; 4] {
+		[
+			f32::from(self.r) / 255.0,
+			f32::from(self.g) / 255.0,
+			f32::from(self.b) / 255.0,
+			f32::from(self.a) / 255.0,
+		]
+	}
+}
+
+/// Color of a face. Besides a color, faces may reference a color of a
+/// colormap (see [`ColorMap`](crate::colormap::ColorMap)) by its index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FaceColor {
+	Color(Color),
+	Index(usize),
 }
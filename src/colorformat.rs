@@ -1,9 +1,30 @@
-use crate::color::{Color, ColorError};
+use std::io;
+
+use crate::color::{Color, ColorError, FaceColor};
 
 pub trait ColorFormat {
+	/// Whether colors in this format consist of any elements. Writers use
+	/// this to decide if per vertex colors can be represented, parsers skip
+	/// the colors of formats without elements.
+	const HAS_ELEMENTS: bool = true;
+
 	fn try_parse<'a>(
 		iter: &mut impl Iterator<Item = &'a str>,
 	) -> Option<Result<Color, ColorError>>;
+
+	/// Parses the color of a face. Only formats which support colormaps (see
+	/// [`Indexed`]) return [`FaceColor::Index`].
+	fn try_parse_face<'a>(
+		iter: &mut impl Iterator<Item = &'a str>,
+	) -> Option<Result<FaceColor, ColorError>> {
+		Self::try_parse(iter).map(|res| res.map(FaceColor::Color))
+	}
+
+	/// Writes the elements of `color` in this format. Each element is
+	/// preceded by a single space so that the output can directly be appended
+	/// to a line.
+	fn try_write(color: &Color, writer: &mut impl io::Write)
+		-> io::Result<()>;
 }
 
 macro_rules! return_err {
@@ -15,15 +36,22 @@ macro_rules! return_err {
 	};
 }
 
+/// Ignores all colors: they are skipped when parsing and not written.
 #[derive(Debug, Clone, Copy, Hash)]
 pub struct None;
 
 impl ColorFormat for None {
+	const HAS_ELEMENTS: bool = false;
+
 	fn try_parse<'a>(
 		_: &mut impl Iterator<Item = &'a str>,
 	) -> Option<Result<Color, ColorError>> {
 		std::option::Option::None
 	}
+
+	fn try_write(_: &Color, _: &mut impl io::Write) -> io::Result<()> {
+		Ok(())
+	}
 }
 
 #[derive(Debug, Clone, Copy, Hash)]
@@ -72,6 +100,15 @@ impl ColorFormat for Any {
 
 		Some(Ok(Color::new(red, green, blue, alpha)))
 	}
+
+	fn try_write(
+		color: &Color,
+		writer: &mut impl io::Write,
+	) -> io::Result<()> {
+		// Bytes are the only representation which can be written and read
+		// back without losing precision.
+		RgbaU8::try_write(color, writer)
+	}
 }
 
 macro_rules! try_parse_convert {
@@ -105,6 +142,13 @@ impl ColorFormat for RgbU8 {
 
 		Some(Ok(Color::new(red, green, blue, 255)))
 	}
+
+	fn try_write(
+		color: &Color,
+		writer: &mut impl io::Write,
+	) -> io::Result<()> {
+		write!(writer, " {} {} {}", color.r, color.g, color.b)
+	}
 }
 
 #[derive(Debug, Clone, Copy, Hash)]
@@ -121,6 +165,13 @@ impl ColorFormat for RgbaU8 {
 
 		Some(Ok(Color::new(red, green, blue, alpha)))
 	}
+
+	fn try_write(
+		color: &Color,
+		writer: &mut impl io::Write,
+	) -> io::Result<()> {
+		write!(writer, " {} {} {} {}", color.r, color.g, color.b, color.a)
+	}
 }
 
 #[derive(Debug, Clone, Copy, Hash)]
@@ -136,6 +187,14 @@ impl ColorFormat for RgbF32 {
 
 		Some(Color::try_from_f32(red, green, blue, 1.0))
 	}
+
+	fn try_write(
+		color: &Color,
+		writer: &mut impl io::Write,
+	) -> io::Result<()> {
+		let [red, green, blue, _] = color.to_f32();
+		write!(writer, " {} {} {}", red, green, blue)
+	}
 }
 
 #[derive(Debug, Clone, Copy, Hash)]
@@ -152,4 +211,77 @@ impl ColorFormat for RgbaF32 {
 
 		Some(Color::try_from_f32(red, green, blue, alpha))
 	}
+
+	fn try_write(
+		color: &Color,
+		writer: &mut impl io::Write,
+	) -> io::Result<()> {
+		let [red, green, blue, alpha] = color.to_f32();
+		write!(writer, " {} {} {} {}", red, green, blue, alpha)
+	}
+}
+
+/// Accepts a single integer as face color, which is recorded as an index
+/// into a colormap (see [`ColorMap`](crate::colormap::ColorMap)). All other
+/// colors are parsed and written with `F`.
+#[derive(Debug, Clone, Copy, Hash)]
+pub struct Indexed<F = Any>(pub F);
+
+impl<F: ColorFormat> ColorFormat for Indexed<F> {
+	const HAS_ELEMENTS: bool = F::HAS_ELEMENTS;
+
+	fn try_parse<'a>(
+		iter: &mut impl Iterator<Item = &'a str>,
+	) -> Option<Result<Color, ColorError>> {
+		F::try_parse(iter)
+	}
+
+	fn try_parse_face<'a>(
+		iter: &mut impl Iterator<Item = &'a str>,
+	) -> Option<Result<FaceColor, ColorError>> {
+		let first = iter.next()?;
+
+		match iter.next() {
+			std::option::Option::None => {
+				if let Ok(index) = first.parse::<usize>() {
+					return Some(Ok(FaceColor::Index(index)));
+				}
+
+				F::try_parse_face(&mut std::iter::once(first))
+			}
+			Some(second) => {
+				F::try_parse_face(&mut [first, second].into_iter().chain(iter))
+			}
+		}
+	}
+
+	fn try_write(
+		color: &Color,
+		writer: &mut impl io::Write,
+	) -> io::Result<()> {
+		F::try_write(color, writer)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn indexed() {
+		let parse = |s: &str| {
+			Indexed::<RgbU8>::try_parse_face(&mut s.split_whitespace())
+		};
+
+		assert_eq!(parse("7"), Some(Ok(FaceColor::Index(7))));
+		assert_eq!(
+			parse("1 2 3"),
+			Some(Ok(FaceColor::Color(Color::new(1, 2, 3, 255))))
+		);
+		assert!(matches!(parse("0.5"), Some(Err(_))));
+		assert_eq!(parse(""), std::option::Option::None);
+
+		let mut words = "7".split_whitespace();
+		assert!(Indexed::<RgbU8>::try_parse(&mut words).unwrap().is_err());
+	}
 }
@@ -0,0 +1,113 @@
+use crate::color::{Color, ColorError, FaceColor, Result};
+
+/// Colors which can be referenced by index from faces (see
+/// [`Indexed`](crate::colorformat::Indexed)).
+///
+/// There is no default colormap. Geomview's own default (`cmap.fmap`) can
+/// be loaded with [`ColorMap::try_parse`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ColorMap {
+	colors: Vec<Color>,
+}
+
+impl ColorMap {
+	pub fn new(colors: Vec<Color>) -> Self {
+		Self { colors }
+	}
+
+	/// Parses a colormap in the format used by Geomview (`.fmap`). Each
+	/// non-empty line contains a color as three or four floats (rgb(a)) in
+	/// the range `0.0 - 1.0`. Comments start with `#`.
+	pub fn try_parse(content: &str) -> Result<Self> {
+		let mut colors = Vec::new();
+
+		for (line_index, line) in crate::parse::OffLines::new(content) {
+			let elements = line
+				.split_whitespace()
+				.map(str::parse::<f32>)
+				.collect::<Result<Vec<_>, _>>()
+				.map_err(|err| {
+					ColorError::FailedToParse(format!(
+						"Invalid element @ ln:{} ({})",
+						line_index + 1,
+						err
+					))
+				})?;
+
+			let color = match elements[..] {
+				[red, green, blue] => {
+					Color::try_from_f32(red, green, blue, 1.0)
+				}
+				[red, green, blue, alpha] => {
+					Color::try_from_f32(red, green, blue, alpha)
+				}
+				_ => {
+					return Err(ColorError::FailedToParse(format!(
+						"Expected 3 or 4 elements @ ln:{} (got: {})",
+						line_index + 1,
+						elements.len()
+					)))
+				}
+			}?;
+
+			colors.push(color);
+		}
+
+		Ok(Self::new(colors))
+	}
+
+	pub fn colors(&self) -> &[Color] {
+		&self.colors
+	}
+
+	pub fn len(&self) -> usize {
+		self.colors.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.colors.is_empty()
+	}
+
+	pub fn get(&self, index: usize) -> Option<Color> {
+		self.colors.get(index).copied()
+	}
+
+	/// Resolves a face color to a `Color`. Returns `None` if the index is
+	/// not part of this colormap.
+	pub fn resolve(&self, color: FaceColor) -> Option<Color> {
+		match color {
+			FaceColor::Color(color) => Some(color),
+			FaceColor::Index(index) => self.get(index),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parse() {
+		let colormap =
+			ColorMap::try_parse("# rgba\n1 0 0 1\n\n0 0 1 # rgb\n").unwrap();
+
+		assert_eq!(
+			colormap.colors(),
+			[Color::new(255, 0, 0, 255), Color::new(0, 0, 255, 255)]
+		);
+
+		assert!(ColorMap::try_parse("1 0\n").is_err());
+		assert!(ColorMap::try_parse("1 0 2\n").is_err());
+		assert!(ColorMap::try_parse("1 0 red\n").is_err());
+	}
+
+	#[test]
+	fn resolve() {
+		let red = Color::new(255, 0, 0, 255);
+		let colormap = ColorMap::new(vec![Color::new(0, 0, 0, 255), red]);
+
+		assert_eq!(colormap.resolve(FaceColor::Index(1)), Some(red));
+		assert_eq!(colormap.resolve(FaceColor::Color(red)), Some(red));
+		assert_eq!(colormap.resolve(FaceColor::Index(2)), None);
+	}
+}
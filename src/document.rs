@@ -1,8 +1,11 @@
+use std::collections::HashSet;
 use std::fmt;
 use std::marker::PhantomData;
 
+use crate::color::Color;
 use crate::face::FaceRef;
-use crate::vertex::Vertex;
+use crate::header::OffHeader;
+use crate::vertex::{Normal, TextureCoord, Vertex, VertexStore};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ValidationError(String);
@@ -13,38 +16,158 @@ impl fmt::Display for ValidationError {
 	}
 }
 
+/// Optional rules for [`Document::validate_with_options`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ValidationOptions {
+	/// Require the declared edge count to match the number of edges of the
+	/// faces (see [`Document::count_edges`]). An edge count of `0` is
+	/// treated as unknown and always accepted.
+	pub edge_count: bool,
+	/// Reject edges shared by more than two faces and vertices at which
+	/// separate fans of faces meet (see [`Document::topology`]).
+	pub manifold: bool,
+	/// Reject edges of only one face.
+	pub closed: bool,
+	/// Reject vertices which are not part of any face.
+	pub isolated_vertices: bool,
+}
+
 mod sealed {
 	pub trait Sealed {}
 }
 
 pub trait State: sealed::Sealed {}
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Unchecked;
 impl sealed::Sealed for Unchecked {}
 impl State for Unchecked {}
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Checked;
 impl sealed::Sealed for Checked {}
 impl State for Checked {}
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone)]
 pub struct Document<S> {
+	pub(crate) header: OffHeader,
 	pub(crate) vertices: Vec<Vertex>,
+	pub(crate) coordinates: Option<VertexStore>,
+	pub(crate) vertex_w: Option<Vec<f32>>,
+	pub(crate) vertex_colors: Option<Vec<Color>>,
+	pub(crate) vertex_normals: Option<Vec<Normal>>,
+	pub(crate) texture_coords: Option<Vec<TextureCoord>>,
 	pub(crate) face_refs: Vec<FaceRef>,
 	pub(crate) edge_count: Option<u64>,
 	pub(crate) _marker: PhantomData<S>,
 }
 
+/// Documents are equal if they describe the same mesh, independent of its
+/// encoding: `binary` of the header is ignored and an edge count of `0` is
+/// the same as an unknown one (binary documents always declare one).
+impl<S> PartialEq for Document<S> {
+	fn eq(&self, other: &Self) -> bool {
+		let header =
+			|document: &Self| OffHeader { binary: false, ..document.header };
+		let edge_count =
+			|document: &Self| document.edge_count.filter(|&count| count != 0);
+
+		header(self) == header(other)
+			&& self.vertices == other.vertices
+			&& self.coordinates == other.coordinates
+			&& self.vertex_w == other.vertex_w
+			&& self.vertex_colors == other.vertex_colors
+			&& self.vertex_normals == other.vertex_normals
+			&& self.texture_coords == other.texture_coords
+			&& self.face_refs == other.face_refs
+			&& edge_count(self) == edge_count(other)
+	}
+}
+
 impl<S> Document<S> {
+	/// Header of the source the document was parsed from. Documents which
+	/// were not parsed have a default header.
+	///
+	/// As the header is kept as parsed, it may describe attributes which are
+	/// not present (e.g. vertex colors parsed with the color format `None`).
+	/// For a header describing the attributes of the document see
+	/// [`Document::layout`].
+	pub fn header(&self) -> &OffHeader {
+		&self.header
+	}
+
+	/// Header (without `binary`) which describes the vertex attributes
+	/// present in this document.
+	pub fn layout(&self) -> OffHeader {
+		OffHeader {
+			texture_coords: self.texture_coords.is_some(),
+			vertex_colors: self.vertex_colors.is_some(),
+			vertex_normals: self.vertex_normals.is_some(),
+			homogeneous: self.vertex_w.is_some(),
+			n_dimensional: self.coordinates.is_some(),
+			binary: false,
+			dimension: self.dimension(),
+		}
+	}
+
 	pub fn vertices(&self) -> &[Vertex] {
 		&self.vertices
 	}
 
+	/// All coordinates of `nOFF` vertices. For these documents
+	/// [`Document::vertices`] is derived from the store and only contains
+	/// the first three coordinates (padded with `0.0`).
+	pub fn coordinates(&self) -> Option<&VertexStore> {
+		self.coordinates.as_ref()
+	}
+
+	/// Dimension of the vertices (excluding `w`).
+	pub fn dimension(&self) -> usize {
+		self.coordinates.as_ref().map_or(3, VertexStore::dimension)
+	}
+
+	/// Per vertex homogeneous component `w` (`4OFF`). If present there is
+	/// exactly one value for each vertex.
+	pub fn vertex_w(&self) -> Option<&[f32]> {
+		self.vertex_w.as_deref()
+	}
+
+	/// Per vertex colors (`COFF`). If present there is exactly one color
+	/// for each vertex.
+	pub fn vertex_colors(&self) -> Option<&[Color]> {
+		self.vertex_colors.as_deref()
+	}
+
+	/// Per vertex normals (`NOFF`). If present there is exactly one normal
+	/// for each vertex.
+	pub fn vertex_normals(&self) -> Option<&[Normal]> {
+		self.vertex_normals.as_deref()
+	}
+
+	/// Per vertex texture coordinates (`STOFF`). If present there is exactly
+	/// one pair of texture coordinates for each vertex.
+	pub fn texture_coords(&self) -> Option<&[TextureCoord]> {
+		self.texture_coords.as_deref()
+	}
+
 	pub fn face_refs(&self) -> &[FaceRef] {
 		&self.face_refs
 	}
+
+	/// Edge count as declared by the source of the document. It is only
+	/// checked if requested (see [`ValidationOptions::edge_count`]).
+	pub fn edge_count(&self) -> Option<u64> {
+		self.edge_count
+	}
+
+	/// Number of unique undirected edges of all faces.
+	pub fn count_edges(&self) -> u64 {
+		self.face_refs
+			.iter()
+			.flat_map(FaceRef::undirected_edges)
+			.collect::<HashSet<_>>()
+			.len() as u64
+	}
 }
 
 impl Document<Unchecked> {
@@ -53,10 +176,109 @@ impl Document<Unchecked> {
 		face_refs: Vec<FaceRef>,
 		edge_count: Option<u64>,
 	) -> Self {
-		Self { vertices, face_refs, edge_count, _marker: PhantomData }
+		Self {
+			header: OffHeader::default(),
+			vertices,
+			coordinates: None,
+			vertex_w: None,
+			vertex_colors: None,
+			vertex_normals: None,
+			texture_coords: None,
+			face_refs,
+			edge_count,
+			_marker: PhantomData,
+		}
+	}
+
+	#[must_use]
+	pub fn with_header(mut self, header: OffHeader) -> Self {
+		self.header = header;
+		self
+	}
+
+	/// Makes the document an `nOFF` document of the given coordinates. The
+	/// vertices are replaced by those derived from the store (see
+	/// [`Document::coordinates`]).
+	#[must_use]
+	pub fn with_coordinates(
+		mut self,
+		coordinates: Option<VertexStore>,
+	) -> Self {
+		if let Some(store) = &coordinates {
+			self.vertices =
+				store.iter().map(Vertex::from_coordinates).collect();
+		}
+
+		self.coordinates = coordinates;
+		self
+	}
+
+	#[must_use]
+	pub fn with_vertex_w(mut self, w: Option<Vec<f32>>) -> Self {
+		self.vertex_w = w;
+		self
+	}
+
+	#[must_use]
+	pub fn with_vertex_colors(mut self, colors: Option<Vec<Color>>) -> Self {
+		self.vertex_colors = colors;
+		self
+	}
+
+	#[must_use]
+	pub fn with_vertex_normals(
+		mut self,
+		normals: Option<Vec<Normal>>,
+	) -> Self {
+		self.vertex_normals = normals;
+		self
+	}
+
+	#[must_use]
+	pub fn with_texture_coords(
+		mut self,
+		texture_coords: Option<Vec<TextureCoord>>,
+	) -> Self {
+		self.texture_coords = texture_coords;
+		self
 	}
 
 	pub fn validate(self) -> Result<Document<Checked>, ValidationError> {
+		self.validate_with_options(ValidationOptions::default())
+	}
+
+	/// Same as [`Document::validate`], but also applies the optional rules
+	/// enabled in `options`.
+	pub fn validate_with_options(
+		self,
+		options: ValidationOptions,
+	) -> Result<Document<Checked>, ValidationError> {
+		let attribute_counts = [
+			("coordinates", self.coordinates.as_ref().map(VertexStore::len)),
+			("w components", self.vertex_w.as_ref().map(Vec::len)),
+			("colors", self.vertex_colors.as_ref().map(Vec::len)),
+			("normals", self.vertex_normals.as_ref().map(Vec::len)),
+			(
+				"texture coordinates",
+				self.texture_coords.as_ref().map(Vec::len),
+			),
+		];
+
+		for (name, count) in attribute_counts {
+			match count {
+				Some(count) if count != self.vertices.len() => {
+					return Err(ValidationError(format!(
+						"Number of vertex {} does not match number of \
+						 vertices ({}/{})",
+						name,
+						count,
+						self.vertices.len()
+					)));
+				}
+				_ => {}
+			}
+		}
+
 		let mut vertex_ref_iter =
 			self.face_refs.iter().flat_map(|fr| &fr.vertex_refs);
 
@@ -67,13 +289,90 @@ impl Document<Unchecked> {
 				"No vertex present for index `{}`",
 				invalid_vertex_index.0
 			)))
+		} else if let Some((declared, actual)) = self
+			.edge_count
+			.filter(|&count| options.edge_count && count != 0)
+			.map(|count| (count, self.count_edges()))
+			.filter(|(declared, actual)| declared != actual)
+		{
+			Err(ValidationError(format!(
+				"Edge count does not match the faces ({}/{})",
+				declared, actual
+			)))
 		} else {
-			Ok(Document {
+			let document = Document {
+				header: self.header,
 				vertices: self.vertices,
+				coordinates: self.coordinates,
+				vertex_w: self.vertex_w,
+				vertex_colors: self.vertex_colors,
+				vertex_normals: self.vertex_normals,
+				texture_coords: self.texture_coords,
 				face_refs: self.face_refs,
 				edge_count: self.edge_count,
 				_marker: PhantomData,
-			})
+			};
+
+			if options.manifold || options.closed || options.isolated_vertices
+			{
+				let report = document.topology();
+
+				if (options.manifold && !report.is_manifold())
+					|| (options.closed && !report.is_closed())
+					|| (options.isolated_vertices
+						&& !report.isolated_vertices.is_empty())
+				{
+					return Err(ValidationError(format!(
+						"Invalid topology: {}",
+						report
+					)));
+				}
+			}
+
+			Ok(document)
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::parse::OffParser;
+
+	#[test]
+	fn edge_count() {
+		let content = include_str!("../resources/wiki.off");
+		let document = OffParser::new(&content).try_parse().unwrap();
+
+		assert_eq!(document.edge_count(), Some(12));
+		assert_eq!(document.count_edges(), 12);
+
+		let options =
+			ValidationOptions { edge_count: true, ..Default::default() };
+		assert!(document.clone().validate_with_options(options).is_ok());
+
+		let content =
+			"OFF\n4 2 4\n0 0 0\n1 0 0\n0 1 0\n1 1 0\n3 0 1 2\n3 2 1 3\n";
+		let document = OffParser::new(&content).try_parse().unwrap();
+
+		assert_eq!(document.count_edges(), 5);
+		assert!(document.clone().validate().is_ok());
+		assert!(document.validate_with_options(options).is_err());
+	}
+
+	#[test]
+	fn equality() {
+		let parse =
+			|content: &str| OffParser::new(&content).try_parse().unwrap();
+
+		let document = parse("OFF\n3 1\n0 0 0\n1 0 0\n0 1 0\n3 0 1 2\n");
+		let unknown = parse("OFF\n3 1 0\n0 0 0\n1 0 0\n0 1 0\n3 0 1 2\n");
+		let declared = parse("OFF\n3 1 3\n0 0 0\n1 0 0\n0 1 0\n3 0 1 2\n");
+
+		assert_eq!(document, unknown);
+		assert_ne!(document, declared);
+
+		let binary = OffHeader { binary: true, ..*document.header() };
+		assert_eq!(document, document.clone().with_header(binary));
+	}
+}
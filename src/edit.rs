@@ -0,0 +1,606 @@
+//! Editing of checked documents.
+//!
+//! All edits keep the guarantees of [`Checked`] documents: every vertex
+//! reference of a face is in bounds and there is exactly one value of each
+//! vertex attribute per vertex. Edits which would break them are rejected
+//! with an [`EditError`].
+//!
+//! Removing vertices or faces shifts the indices of all following vertices
+//! or faces, so previously obtained references may become invalid. Edits of
+//! the faces also drop the declared edge count, as it would no longer
+//! describe the document.
+
+use std::fmt;
+
+use crate::color::{Color, FaceColor};
+use crate::document::{Checked, Document};
+use crate::face::FaceRef;
+use crate::parse::VertexAttributes;
+use crate::vertex::{Normal, TextureCoord, Vertex, VertexRef, VertexStore};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EditError {
+	/// No vertex is present for the index.
+	InvalidVertex(usize),
+	/// No face is present for the index.
+	InvalidFace(usize),
+	/// The attributes of a vertex do not match those of the document.
+	Attributes(String),
+}
+
+impl fmt::Display for EditError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::InvalidVertex(index) => {
+				write!(f, "No vertex present for index `{}`", index)
+			}
+			Self::InvalidFace(index) => {
+				write!(f, "No face present for index `{}`", index)
+			}
+			Self::Attributes(msg) => write!(f, "Invalid attributes: {}", msg),
+		}
+	}
+}
+
+impl std::error::Error for EditError {}
+
+pub type Result<T, E = EditError> = std::result::Result<T, E>;
+
+/// Checks that an attribute is given if and only if the document has it.
+fn check_attribute<T>(
+	name: &str,
+	expected: bool,
+	value: &Option<T>,
+) -> Result<()> {
+	match (expected, value.is_some()) {
+		(true, false) => Err(EditError::Attributes(format!(
+			"Vertex {} are required by the document",
+			name
+		))),
+		(false, true) => Err(EditError::Attributes(format!(
+			"Document has no vertex {}",
+			name
+		))),
+		_ => Ok(()),
+	}
+}
+
+/// Replaces the value of a vertex attribute, if the document has it.
+fn set_attribute<T>(
+	name: &str,
+	values: &mut Option<Vec<T>>,
+	index: usize,
+	value: T,
+) -> Result<()> {
+	match values {
+		Some(values) => {
+			values[index] = value;
+			Ok(())
+		}
+		None => Err(EditError::Attributes(format!(
+			"Document has no vertex {}",
+			name
+		))),
+	}
+}
+
+/// Keeps the items for which `keep` is `true`.
+fn retain_by<T>(items: &mut Vec<T>, keep: &[bool]) {
+	let mut keep = keep.iter();
+	items.retain(|_| *keep.next().unwrap_or(&true));
+}
+
+impl Document<Checked> {
+	/// Adds a vertex with all attributes of the document and returns a
+	/// reference to it.
+	///
+	/// For `nOFF` documents `vertex` is replaced by the first three of the
+	/// coordinates in `attributes` (padded with `0.0`), the same way as for
+	/// parsed documents.
+	pub fn add_vertex(
+		&mut self,
+		mut vertex: Vertex,
+		attributes: VertexAttributes,
+	) -> Result<VertexRef> {
+		check_attribute(
+			"coordinates",
+			self.coordinates.is_some(),
+			&attributes.coordinates,
+		)?;
+		check_attribute(
+			"w components",
+			self.vertex_w.is_some(),
+			&attributes.w,
+		)?;
+		check_attribute(
+			"colors",
+			self.vertex_colors.is_some(),
+			&attributes.color,
+		)?;
+		check_attribute(
+			"normals",
+			self.vertex_normals.is_some(),
+			&attributes.normal,
+		)?;
+		check_attribute(
+			"texture coordinates",
+			self.texture_coords.is_some(),
+			&attributes.texture_coord,
+		)?;
+
+		if let (Some(store), Some(coordinates)) =
+			(&mut self.coordinates, &attributes.coordinates)
+		{
+			if coordinates.len() != store.dimension() {
+				return Err(EditError::Attributes(format!(
+					"Number of coordinates does not match dimension ({}/{})",
+					coordinates.len(),
+					store.dimension()
+				)));
+			}
+
+			store.push(coordinates);
+			vertex = Vertex::from_coordinates(coordinates);
+		}
+
+		// All attributes are checked, so that each one grows by one value
+		if let (Some(w), Some(value)) = (&mut self.vertex_w, attributes.w) {
+			w.push(value);
+		}
+
+		if let (Some(colors), Some(color)) =
+			(&mut self.vertex_colors, attributes.color)
+		{
+			colors.push(color);
+		}
+
+		if let (Some(normals), Some(normal)) =
+			(&mut self.vertex_normals, attributes.normal)
+		{
+			normals.push(normal);
+		}
+
+		if let (Some(texture_coords), Some(texture_coord)) =
+			(&mut self.texture_coords, attributes.texture_coord)
+		{
+			texture_coords.push(texture_coord);
+		}
+
+		self.vertices.push(vertex);
+
+		Ok(VertexRef(self.vertices.len() - 1))
+	}
+
+	/// Moves a vertex. For `nOFF` documents the first three coordinates are
+	/// updated as well (see [`Document::set_coordinates`]). Fails if the
+	/// dimension is lower and a coordinate beyond it is not `0.0`.
+	pub fn set_vertex(
+		&mut self,
+		vertex_ref: VertexRef,
+		vertex: Vertex,
+	) -> Result<()> {
+		let index = self.check_vertex(vertex_ref)?;
+
+		match &mut self.coordinates {
+			Some(store) => {
+				let dimension = store.dimension();
+				let values = [vertex.x, vertex.y, vertex.z];
+
+				if values.iter().skip(dimension).any(|&value| value != 0.0) {
+					return Err(EditError::Attributes(format!(
+						"Vertex has more coordinates than the dimension ({})",
+						dimension
+					)));
+				}
+
+				let coordinates = &mut store.get_mut(index).unwrap()
+					[..dimension.min(values.len())];
+				coordinates.copy_from_slice(&values[..coordinates.len()]);
+			}
+			None => self.vertices[index] = vertex,
+		}
+
+		self.sync_vertex(index);
+
+		Ok(())
+	}
+
+	/// Changes all coordinates of a vertex of an `nOFF` document. Fails if
+	/// the document is not `nOFF` or the number of coordinates does not
+	/// match its dimension.
+	pub fn set_coordinates(
+		&mut self,
+		vertex_ref: VertexRef,
+		coordinates: &[f32],
+	) -> Result<()> {
+		let index = self.check_vertex(vertex_ref)?;
+		let store = self.coordinates.as_mut().ok_or_else(|| {
+			EditError::Attributes("Document has no coordinates".into())
+		})?;
+
+		if coordinates.len() != store.dimension() {
+			return Err(EditError::Attributes(format!(
+				"Number of coordinates does not match dimension ({}/{})",
+				coordinates.len(),
+				store.dimension()
+			)));
+		}
+
+		store.get_mut(index).unwrap().copy_from_slice(coordinates);
+		self.sync_vertex(index);
+
+		Ok(())
+	}
+
+	/// Changes the homogeneous component `w` of a vertex. Fails if the
+	/// document has no `w` components.
+	pub fn set_vertex_w(
+		&mut self,
+		vertex_ref: VertexRef,
+		w: f32,
+	) -> Result<()> {
+		let index = self.check_vertex(vertex_ref)?;
+		set_attribute("w components", &mut self.vertex_w, index, w)
+	}
+
+	/// Changes the color of a vertex. Fails if the document has no vertex
+	/// colors.
+	pub fn set_vertex_color(
+		&mut self,
+		vertex_ref: VertexRef,
+		color: Color,
+	) -> Result<()> {
+		let index = self.check_vertex(vertex_ref)?;
+		set_attribute("colors", &mut self.vertex_colors, index, color)
+	}
+
+	/// Changes the normal of a vertex. Fails if the document has no vertex
+	/// normals.
+	pub fn set_vertex_normal(
+		&mut self,
+		vertex_ref: VertexRef,
+		normal: Normal,
+	) -> Result<()> {
+		let index = self.check_vertex(vertex_ref)?;
+		set_attribute("normals", &mut self.vertex_normals, index, normal)
+	}
+
+	/// Changes the texture coordinates of a vertex. Fails if the document
+	/// has no texture coordinates.
+	pub fn set_texture_coord(
+		&mut self,
+		vertex_ref: VertexRef,
+		texture_coord: TextureCoord,
+	) -> Result<()> {
+		let index = self.check_vertex(vertex_ref)?;
+		set_attribute(
+			"texture coordinates",
+			&mut self.texture_coords,
+			index,
+			texture_coord,
+		)
+	}
+
+	/// Removes a vertex together with all faces which reference it and
+	/// returns it.
+	///
+	/// The indices of all following vertices are decreased by one.
+	pub fn remove_vertex(&mut self, vertex_ref: VertexRef) -> Result<Vertex> {
+		let index = self.check_vertex(vertex_ref)?;
+
+		let mut keep = vec![true; self.vertices.len()];
+		keep[index] = false;
+
+		self.face_refs
+			.retain(|face_ref| !face_ref.vertex_refs.contains(&vertex_ref));
+
+		let vertex = self.vertices[index];
+		self.retain_vertices(&keep);
+		self.edge_count = None;
+
+		Ok(vertex)
+	}
+
+	/// Removes all vertices which are not referenced by any face and returns
+	/// how many were removed.
+	pub fn remove_unused_vertices(&mut self) -> usize {
+		let mut keep = vec![false; self.vertices.len()];
+
+		for vertex_ref in self.face_refs.iter().flat_map(|fr| &fr.vertex_refs)
+		{
+			keep[vertex_ref.0] = true;
+		}
+
+		let removed = keep.iter().filter(|&&keep| !keep).count();
+
+		if removed > 0 {
+			self.retain_vertices(&keep);
+		}
+
+		removed
+	}
+
+	/// Adds a face and returns its index.
+	pub fn add_face(
+		&mut self,
+		vertex_refs: Vec<VertexRef>,
+		color: Option<FaceColor>,
+	) -> Result<usize> {
+		self.check_vertex_refs(&vertex_refs)?;
+
+		self.face_refs.push(FaceRef::new(vertex_refs, color));
+		self.edge_count = None;
+
+		Ok(self.face_refs.len() - 1)
+	}
+
+	/// Replaces the vertices of a face. Its color is kept.
+	pub fn set_face_vertices(
+		&mut self,
+		index: usize,
+		vertex_refs: Vec<VertexRef>,
+	) -> Result<()> {
+		self.check_face(index)?;
+		self.check_vertex_refs(&vertex_refs)?;
+
+		self.face_refs[index].vertex_refs = vertex_refs;
+		self.edge_count = None;
+
+		Ok(())
+	}
+
+	/// Changes (or removes) the color of a face.
+	pub fn set_face_color(
+		&mut self,
+		index: usize,
+		color: Option<FaceColor>,
+	) -> Result<()> {
+		self.check_face(index)?;
+
+		let face_ref = &mut self.face_refs[index];
+		*face_ref =
+			FaceRef::new(std::mem::take(&mut face_ref.vertex_refs), color);
+
+		Ok(())
+	}
+
+	/// Removes a face and returns it. The indices of all following faces
+	/// are decreased by one. Its vertices are kept (see
+	/// [`Document::remove_unused_vertices`]).
+	pub fn remove_face(&mut self, index: usize) -> Result<FaceRef> {
+		self.check_face(index)?;
+		self.edge_count = None;
+
+		Ok(self.face_refs.remove(index))
+	}
+
+	/// Derives the vertex from its coordinates for `nOFF` documents.
+	fn sync_vertex(&mut self, index: usize) {
+		if let Some(coordinates) =
+			self.coordinates.as_ref().and_then(|store| store.get(index))
+		{
+			self.vertices[index] = Vertex::from_coordinates(coordinates);
+		}
+	}
+
+	fn check_vertex(&self, vertex_ref: VertexRef) -> Result<usize> {
+		if vertex_ref.0 < self.vertices.len() {
+			Ok(vertex_ref.0)
+		} else {
+			Err(EditError::InvalidVertex(vertex_ref.0))
+		}
+	}
+
+	fn check_vertex_refs(&self, vertex_refs: &[VertexRef]) -> Result<()> {
+		vertex_refs.iter().try_for_each(|&vertex_ref| {
+			self.check_vertex(vertex_ref).map(drop)
+		})
+	}
+
+	fn check_face(&self, index: usize) -> Result<()> {
+		if index < self.face_refs.len() {
+			Ok(())
+		} else {
+			Err(EditError::InvalidFace(index))
+		}
+	}
+
+	/// Keeps the vertices (and their attributes) for which `keep` is `true`
+	/// and updates the references of the faces. Faces must not reference
+	/// removed vertices.
+	fn retain_vertices(&mut self, keep: &[bool]) {
+		let remap = keep
+			.iter()
+			.scan(0, |next, &keep| {
+				let index = *next;
+				*next += usize::from(keep);
+				Some(index)
+			})
+			.collect::<Vec<_>>();
+
+		for vertex_ref in
+			self.face_refs.iter_mut().flat_map(|fr| &mut fr.vertex_refs)
+		{
+			debug_assert!(keep[vertex_ref.0]);
+			vertex_ref.0 = remap[vertex_ref.0];
+		}
+
+		retain_by(&mut self.vertices, keep);
+
+		if let Some(w) = &mut self.vertex_w {
+			retain_by(w, keep);
+		}
+
+		if let Some(colors) = &mut self.vertex_colors {
+			retain_by(colors, keep);
+		}
+
+		if let Some(normals) = &mut self.vertex_normals {
+			retain_by(normals, keep);
+		}
+
+		if let Some(texture_coords) = &mut self.texture_coords {
+			retain_by(texture_coords, keep);
+		}
+
+		if let Some(store) = &mut self.coordinates {
+			let mut retained = VertexStore::with_capacity(
+				store.dimension(),
+				self.vertices.len(),
+			);
+
+			for (coordinates, _) in
+				store.iter().zip(keep).filter(|(_, &keep)| keep)
+			{
+				retained.push(coordinates);
+			}
+
+			*store = retained;
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::colorformat::RgbaF32;
+	use crate::parse::{OffParser, ParserOptions};
+	use crate::write::OffWriter;
+
+	fn document() -> Document<Checked> {
+		let content = "COFF\n4 2 5\n0 0 0 1 0 0 1\n1 0 0 0 1 0 1\n0 1 0 0 0 \
+		               1 1\n1 1 0 1 1 1 1\n3 0 1 2\n3 2 1 3\n";
+
+		OffParser::new_with_options(
+			&content,
+			ParserOptions::with_color_format(RgbaF32),
+		)
+		.try_parse()
+		.unwrap()
+		.validate()
+		.unwrap()
+	}
+
+	#[test]
+	fn edit_vertices() {
+		let mut document = document();
+		let white = Color::new(255, 255, 255, 255);
+
+		assert!(matches!(
+			document.add_vertex(
+				Vertex::new(2.0, 2.0, 0.0),
+				VertexAttributes::default()
+			),
+			Err(EditError::Attributes(_))
+		));
+
+		let vertex_ref = document
+			.add_vertex(
+				Vertex::new(2.0, 2.0, 0.0),
+				VertexAttributes { color: Some(white), ..Default::default() },
+			)
+			.unwrap();
+		assert_eq!(vertex_ref.index(), 4);
+		assert_eq!(document.vertex_colors().unwrap()[4], white);
+
+		document.set_vertex(vertex_ref, Vertex::new(3.0, 3.0, 0.0)).unwrap();
+		assert_eq!(document.vertices()[4], Vertex::new(3.0, 3.0, 0.0));
+
+		assert_eq!(
+			document.set_vertex_color(VertexRef(5), white),
+			Err(EditError::InvalidVertex(5))
+		);
+		assert!(matches!(
+			document
+				.set_vertex_normal(VertexRef(0), Normal::new(0.0, 0.0, 1.0)),
+			Err(EditError::Attributes(_))
+		));
+
+		// Removes the second face and shifts the last vertex
+		let face = document.face_refs()[1].clone();
+		document.remove_vertex(VertexRef(3)).unwrap();
+		assert_eq!(document.vertices().len(), 4);
+		assert_eq!(document.vertex_colors().unwrap().len(), 4);
+		assert_eq!(document.vertices()[3], Vertex::new(3.0, 3.0, 0.0));
+		assert_eq!(document.face_refs().len(), 1);
+		assert!(!document.face_refs().contains(&face));
+		assert_eq!(document.edge_count(), None);
+
+		assert_eq!(document.remove_unused_vertices(), 1);
+		assert_eq!(document.vertices().len(), 3);
+		assert_eq!(document.face_iter().count(), 1);
+	}
+
+	#[test]
+	fn edit_coordinates() {
+		let content = "nOFF\n2\n2 0\n0 0\n1 1\n";
+		let mut document =
+			OffParser::new(&content).try_parse().unwrap().validate().unwrap();
+
+		assert!(matches!(
+			document.set_vertex(VertexRef(0), Vertex::new(1.0, 2.0, 3.0)),
+			Err(EditError::Attributes(_))
+		));
+		document.set_vertex(VertexRef(0), Vertex::new(1.0, 2.0, 0.0)).unwrap();
+
+		assert!(matches!(
+			document.set_coordinates(VertexRef(1), &[1.0, 2.0, 3.0]),
+			Err(EditError::Attributes(_))
+		));
+		document.set_coordinates(VertexRef(1), &[3.0, 4.0]).unwrap();
+
+		assert_eq!(
+			document.vertices(),
+			[Vertex::new(1.0, 2.0, 0.0), Vertex::new(3.0, 4.0, 0.0)]
+		);
+
+		let written = OffWriter::new(&document).write_string();
+		let reparsed = OffParser::new(&written).try_parse().unwrap();
+		assert_eq!(reparsed.vertices(), document.vertices());
+		assert_eq!(reparsed.coordinates(), document.coordinates());
+
+		let mut document = self::document();
+		assert!(matches!(
+			document.set_coordinates(VertexRef(0), &[1.0, 2.0, 3.0]),
+			Err(EditError::Attributes(_))
+		));
+	}
+
+	#[test]
+	fn edit_faces() {
+		let mut document = document();
+		let red = FaceColor::Color(Color::new(255, 0, 0, 255));
+
+		assert_eq!(
+			document.add_face(vec![VertexRef(0), VertexRef(4)], None),
+			Err(EditError::InvalidVertex(4))
+		);
+
+		let index = document
+			.add_face(vec![VertexRef(0), VertexRef(2), VertexRef(3)], None)
+			.unwrap();
+		assert_eq!(index, 2);
+		assert_eq!(document.edge_count(), None);
+
+		document.set_face_color(0, Some(red)).unwrap();
+		document
+			.set_face_vertices(
+				0,
+				vec![VertexRef(1), VertexRef(3), VertexRef(0)],
+			)
+			.unwrap();
+		assert_eq!(document.face_refs()[0].face_color(), Some(red));
+		assert_eq!(
+			document.face_refs()[0].vertex_refs,
+			[VertexRef(1), VertexRef(3), VertexRef(0)]
+		);
+
+		let face = document.remove_face(1).unwrap();
+		assert_eq!(
+			face.vertex_refs,
+			[VertexRef(2), VertexRef(1), VertexRef(3)]
+		);
+		assert_eq!(document.remove_face(2), Err(EditError::InvalidFace(2)));
+		assert_eq!(document.face_iter().count(), 2);
+	}
+}
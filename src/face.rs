@@ -1,14 +1,79 @@
-use crate::color::Color;
-use crate::vertex::{Vertex, VertexRef};
+use crate::color::{Color, FaceColor};
+use crate::colormap::ColorMap;
+use crate::vertex::{Normal, TextureCoord, Vertex, VertexRef};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct FaceRef {
 	pub(crate) vertex_refs: Vec<VertexRef>,
 	pub(crate) color: Option<Color>,
+	/// Index into a colormap; only present if `color` is not.
+	pub(crate) colormap_index: Option<usize>,
+}
+
+impl FaceRef {
+	pub(crate) fn new(
+		vertex_refs: Vec<VertexRef>,
+		color: Option<FaceColor>,
+	) -> Self {
+		let (color, colormap_index) = match color {
+			Some(FaceColor::Color(color)) => (Some(color), None),
+			Some(FaceColor::Index(index)) => (None, Some(index)),
+			None => (None, None),
+		};
+
+		Self { vertex_refs, color, colormap_index }
+	}
+
+	/// Vertices of the face in order.
+	pub fn vertex_refs(&self) -> &[VertexRef] {
+		&self.vertex_refs
+	}
+
+	pub fn face_color(&self) -> Option<FaceColor> {
+		self.color
+			.map(FaceColor::Color)
+			.or(self.colormap_index.map(FaceColor::Index))
+	}
+
+	/// Edges of the face as pairs of consecutive vertices, including the
+	/// edge from the last back to the first vertex.
+	pub fn edges(&self) -> impl Iterator<Item = (VertexRef, VertexRef)> + '_ {
+		let next = self.vertex_refs.iter().cycle().skip(1);
+
+		self.vertex_refs.iter().copied().zip(next.copied())
+	}
+
+	/// Edges with their vertices ordered by index, so that both directions
+	/// of an edge are equal. Edges of a vertex to itself are skipped.
+	pub(crate) fn undirected_edges(
+		&self,
+	) -> impl Iterator<Item = (usize, usize)> + '_ {
+		self.edges()
+			.filter(|(a, b)| a != b)
+			.map(|(a, b)| (a.0.min(b.0), a.0.max(b.0)))
+	}
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct ResolvedFaceRef<'a> {
 	pub vertices: Vec<&'a Vertex>,
+	pub coordinates: Option<Vec<&'a [f32]>>,
+	pub vertex_w: Option<Vec<&'a f32>>,
+	pub vertex_colors: Option<Vec<&'a Color>>,
+	pub vertex_normals: Option<Vec<&'a Normal>>,
+	pub texture_coords: Option<Vec<&'a TextureCoord>>,
 	pub color: Option<&'a Color>,
+	pub colormap_index: Option<usize>,
+}
+
+impl ResolvedFaceRef<'_> {
+	/// Color of the face, either given directly or resolved from the
+	/// colormap.
+	pub fn resolve_color(&self, colormap: &ColorMap) -> Option<Color> {
+		match (self.color, self.colormap_index) {
+			(Some(color), _) => Some(*color),
+			(None, Some(index)) => colormap.get(index),
+			(None, None) => None,
+		}
+	}
 }
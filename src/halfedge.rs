@@ -0,0 +1,480 @@
+//! Half-edge representation of the faces of a document.
+//!
+//! Each face is split into half-edges, one for each pair of consecutive
+//! vertices. A half-edge starts at its `origin`, belongs to exactly one face
+//! and links to the `next` and previous half-edge of that face. Its `twin`
+//! is the half-edge of the neighbouring face going in the opposite direction
+//! (if there is a neighbouring face).
+//!
+//! Only manifold meshes with consistently oriented faces can be represented,
+//! anything else is reported as [`HalfEdgeError`].
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::document::{Checked, Document};
+use crate::face::FaceRef;
+use crate::vertex::VertexRef;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HalfEdgeError {
+	/// Face (index) with less than three vertices or a vertex which is used
+	/// more than once.
+	DegenerateFace(usize),
+	/// Edge shared by more than two faces.
+	NonManifoldEdge(VertexRef, VertexRef),
+	/// Edge which two faces traverse in the same direction (see
+	/// [`Document::orient_faces`]).
+	InconsistentOrientation(VertexRef, VertexRef),
+	/// Vertex at which several separate fans of faces meet.
+	NonManifoldVertex(VertexRef),
+}
+
+impl fmt::Display for HalfEdgeError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::DegenerateFace(index) => {
+				write!(f, "Face `{}` is degenerate", index)
+			}
+			Self::NonManifoldEdge(a, b) => write!(
+				f,
+				"Edge `{}-{}` is shared by more than two faces",
+				a.0, b.0
+			),
+			Self::InconsistentOrientation(a, b) => write!(
+				f,
+				"Edge `{}-{}` is traversed in the same direction by two faces",
+				a.0, b.0
+			),
+			Self::NonManifoldVertex(vertex_ref) => {
+				write!(f, "Vertex `{}` is not manifold", vertex_ref.0)
+			}
+		}
+	}
+}
+
+impl std::error::Error for HalfEdgeError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HalfEdgeRef(pub(crate) usize);
+
+impl HalfEdgeRef {
+	/// Index of the referenced half-edge.
+	pub fn index(self) -> usize {
+		self.0
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct HalfEdge {
+	origin: VertexRef,
+	next: HalfEdgeRef,
+	prev: HalfEdgeRef,
+	twin: Option<HalfEdgeRef>,
+	face: usize,
+}
+
+/// Half-edge mesh of a [`Document<Checked>`].
+///
+/// Half-edges are referenced by [`HalfEdgeRef`], vertices by [`VertexRef`]
+/// and faces by their index in the document. Passing references which are
+/// not part of the mesh panics.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HalfEdgeMesh {
+	document: Document<Checked>,
+	half_edges: Vec<HalfEdge>,
+	/// First half-edge of each face
+	face_half_edges: Vec<HalfEdgeRef>,
+	/// Outgoing half-edge of each vertex; a boundary one if there is any
+	vertex_half_edges: Vec<Option<HalfEdgeRef>>,
+}
+
+impl HalfEdgeMesh {
+	pub fn from_document(
+		document: &Document<Checked>,
+	) -> Result<Self, HalfEdgeError> {
+		let mut half_edges = Vec::new();
+		let mut face_half_edges = Vec::with_capacity(document.face_refs.len());
+
+		for (face, face_ref) in document.face_refs.iter().enumerate() {
+			let refs = &face_ref.vertex_refs;
+			let count = refs.len();
+			let duplicate = refs
+				.iter()
+				.enumerate()
+				.any(|(i, vertex_ref)| refs[i + 1..].contains(vertex_ref));
+
+			if count < 3 || duplicate {
+				return Err(HalfEdgeError::DegenerateFace(face));
+			}
+
+			let first = half_edges.len();
+			face_half_edges.push(HalfEdgeRef(first));
+
+			half_edges.extend(refs.iter().enumerate().map(|(i, &origin)| {
+				HalfEdge {
+					origin,
+					next: HalfEdgeRef(first + (i + 1) % count),
+					prev: HalfEdgeRef(first + (i + count - 1) % count),
+					twin: None,
+					face,
+				}
+			}));
+		}
+
+		let target =
+			|half_edge: &HalfEdge| half_edges[half_edge.next.0].origin;
+
+		let mut undirected = HashMap::<(usize, usize), usize>::new();
+		let mut directed = HashMap::new();
+
+		for (index, half_edge) in half_edges.iter().enumerate() {
+			let (a, b) = (half_edge.origin, target(half_edge));
+			let count =
+				undirected.entry((a.0.min(b.0), a.0.max(b.0))).or_default();
+			*count += 1;
+
+			if *count > 2 {
+				return Err(HalfEdgeError::NonManifoldEdge(a, b));
+			}
+
+			if directed.insert((a, b), HalfEdgeRef(index)).is_some() {
+				return Err(HalfEdgeError::InconsistentOrientation(a, b));
+			}
+		}
+
+		let twins = half_edges
+			.iter()
+			.map(|half_edge| {
+				directed.get(&(target(half_edge), half_edge.origin)).copied()
+			})
+			.collect::<Vec<_>>();
+
+		for (half_edge, twin) in half_edges.iter_mut().zip(twins) {
+			half_edge.twin = twin;
+		}
+
+		let mut vertex_half_edges = vec![None; document.vertices.len()];
+		let mut outgoing = vec![0; document.vertices.len()];
+
+		for (index, half_edge) in half_edges.iter().enumerate() {
+			let vertex = half_edge.origin.0;
+			outgoing[vertex] += 1;
+
+			// Circulating from a boundary half-edge reaches all others
+			if vertex_half_edges[vertex].is_none() || half_edge.twin.is_none()
+			{
+				vertex_half_edges[vertex] = Some(HalfEdgeRef(index));
+			}
+		}
+
+		let mesh = Self {
+			document: document.clone(),
+			half_edges,
+			face_half_edges,
+			vertex_half_edges,
+		};
+
+		// A vertex with several fans has half-edges which can not be reached
+		// by circulating around it
+		for (vertex, &count) in outgoing.iter().enumerate() {
+			if count > 0
+				&& mesh.vertex_half_edges(VertexRef(vertex)).count() != count
+			{
+				return Err(HalfEdgeError::NonManifoldVertex(VertexRef(
+					vertex,
+				)));
+			}
+		}
+
+		Ok(mesh)
+	}
+
+	/// Document the mesh was created from.
+	pub fn document(&self) -> &Document<Checked> {
+		&self.document
+	}
+
+	/// Converts the mesh back into a document. The vertices of each face
+	/// are given by its half-edges, all other data is kept.
+	pub fn into_document(self) -> Document<Checked> {
+		let face_refs = (0..self.face_half_edges.len())
+			.map(|face| {
+				let vertex_refs = self.face_vertices(face).collect();
+				FaceRef::new(
+					vertex_refs,
+					self.document.face_refs[face].face_color(),
+				)
+			})
+			.collect();
+
+		Document { face_refs, ..self.document }
+	}
+
+	pub fn half_edge_count(&self) -> usize {
+		self.half_edges.len()
+	}
+
+	pub fn face_count(&self) -> usize {
+		self.face_half_edges.len()
+	}
+
+	pub fn vertex_count(&self) -> usize {
+		self.vertex_half_edges.len()
+	}
+
+	pub fn half_edges(&self) -> impl Iterator<Item = HalfEdgeRef> {
+		(0..self.half_edges.len()).map(HalfEdgeRef)
+	}
+
+	/// Vertex the half-edge starts at.
+	pub fn origin(&self, half_edge: HalfEdgeRef) -> VertexRef {
+		self.half_edges[half_edge.0].origin
+	}
+
+	/// Vertex the half-edge ends at.
+	pub fn target(&self, half_edge: HalfEdgeRef) -> VertexRef {
+		self.origin(self.next(half_edge))
+	}
+
+	pub fn next(&self, half_edge: HalfEdgeRef) -> HalfEdgeRef {
+		self.half_edges[half_edge.0].next
+	}
+
+	pub fn prev(&self, half_edge: HalfEdgeRef) -> HalfEdgeRef {
+		self.half_edges[half_edge.0].prev
+	}
+
+	/// Opposite half-edge of the neighbouring face. `None` for half-edges
+	/// on the boundary of the mesh.
+	pub fn twin(&self, half_edge: HalfEdgeRef) -> Option<HalfEdgeRef> {
+		self.half_edges[half_edge.0].twin
+	}
+
+	/// Index of the face the half-edge belongs to.
+	pub fn face(&self, half_edge: HalfEdgeRef) -> usize {
+		self.half_edges[half_edge.0].face
+	}
+
+	pub fn is_boundary(&self, half_edge: HalfEdgeRef) -> bool {
+		self.twin(half_edge).is_none()
+	}
+
+	/// First half-edge of a face (starting at its first vertex).
+	pub fn face_half_edge(&self, face: usize) -> HalfEdgeRef {
+		self.face_half_edges[face]
+	}
+
+	/// Outgoing half-edge of a vertex. `None` for vertices which are not
+	/// part of any face.
+	pub fn vertex_half_edge(&self, vertex: VertexRef) -> Option<HalfEdgeRef> {
+		self.vertex_half_edges[vertex.0]
+	}
+
+	pub fn is_boundary_vertex(&self, vertex: VertexRef) -> bool {
+		self.vertex_half_edge(vertex)
+			.is_some_and(|half_edge| self.is_boundary(half_edge))
+	}
+
+	/// Half-edges of a face in order.
+	pub fn face_half_edges(&self, face: usize) -> FaceCirculator<'_> {
+		let start = self.face_half_edge(face);
+
+		FaceCirculator { mesh: self, start, current: Some(start) }
+	}
+
+	/// Vertices of a face in order.
+	pub fn face_vertices(
+		&self,
+		face: usize,
+	) -> impl Iterator<Item = VertexRef> + '_ {
+		self.face_half_edges(face).map(|half_edge| self.origin(half_edge))
+	}
+
+	/// Outgoing half-edges of a vertex, each one in the next face around the
+	/// vertex.
+	pub fn vertex_half_edges(
+		&self,
+		vertex: VertexRef,
+	) -> VertexCirculator<'_> {
+		let start = self.vertex_half_edge(vertex);
+
+		VertexCirculator { mesh: self, start, current: start }
+	}
+}
+
+/// Iterator over the half-edges of a face (see
+/// [`HalfEdgeMesh::face_half_edges`]).
+#[derive(Debug, Clone)]
+pub struct FaceCirculator<'a> {
+	mesh: &'a HalfEdgeMesh,
+	start: HalfEdgeRef,
+	current: Option<HalfEdgeRef>,
+}
+
+impl Iterator for FaceCirculator<'_> {
+	type Item = HalfEdgeRef;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let current = self.current?;
+		let next = self.mesh.next(current);
+		self.current = (next != self.start).then_some(next);
+
+		Some(current)
+	}
+}
+
+/// Iterator over the outgoing half-edges of a vertex (see
+/// [`HalfEdgeMesh::vertex_half_edges`]).
+#[derive(Debug, Clone)]
+pub struct VertexCirculator<'a> {
+	mesh: &'a HalfEdgeMesh,
+	start: Option<HalfEdgeRef>,
+	current: Option<HalfEdgeRef>,
+}
+
+impl Iterator for VertexCirculator<'_> {
+	type Item = HalfEdgeRef;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let current = self.current?;
+		// The incoming half-edge of the same face continues in the next face
+		let next = self.mesh.twin(self.mesh.prev(current));
+		self.current = next.filter(|&next| Some(next) != self.start);
+
+		Some(current)
+	}
+}
+
+impl Document<Checked> {
+	/// Creates the half-edge mesh of the document (see [`HalfEdgeMesh`]).
+	pub fn half_edge_mesh(&self) -> Result<HalfEdgeMesh, HalfEdgeError> {
+		HalfEdgeMesh::from_document(self)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::vertex::Vertex;
+
+	/// Document with the given faces of vertices on a grid.
+	fn document(faces: &[&[usize]]) -> Document<Checked> {
+		let mut builder = Document::build();
+		let vertex_count = faces
+			.iter()
+			.flat_map(|face| face.iter())
+			.max()
+			.map_or(0, |i| i + 1);
+		let vertex_refs = (0..vertex_count)
+			.map(|i| {
+				builder.add_vertex(Vertex::new(
+					(i % 3) as f32,
+					(i / 3) as f32,
+					0.0,
+				))
+			})
+			.collect::<Vec<_>>();
+
+		for face in faces {
+			builder
+				.add_face(face.iter().map(|&i| vertex_refs[i]).collect(), None)
+				.unwrap();
+		}
+
+		builder.finish()
+	}
+
+	fn cube() -> Document<Checked> {
+		document(&[
+			&[0, 2, 3, 1],
+			&[4, 5, 7, 6],
+			&[0, 1, 5, 4],
+			&[2, 6, 7, 3],
+			&[0, 4, 6, 2],
+			&[1, 3, 7, 5],
+		])
+	}
+
+	#[test]
+	fn closed() {
+		let document = cube();
+		let mesh = document.half_edge_mesh().unwrap();
+
+		assert_eq!(mesh.half_edge_count(), 24);
+		assert!(mesh.half_edges().all(|half_edge| {
+			let twin = mesh.twin(half_edge).unwrap();
+			mesh.twin(twin) == Some(half_edge)
+				&& mesh.origin(twin) == mesh.target(half_edge)
+		}));
+
+		for vertex in 0..8 {
+			let vertex = VertexRef(vertex);
+			let outgoing = mesh.vertex_half_edges(vertex).collect::<Vec<_>>();
+
+			assert_eq!(outgoing.len(), 3);
+			assert!(outgoing
+				.iter()
+				.all(|&half_edge| mesh.origin(half_edge) == vertex));
+			assert!(!mesh.is_boundary_vertex(vertex));
+		}
+
+		assert_eq!(
+			mesh.face_vertices(3).collect::<Vec<_>>(),
+			document.face_refs()[3].vertex_refs
+		);
+		assert_eq!(mesh.into_document(), document);
+	}
+
+	#[test]
+	fn boundary() {
+		// 0 - 1 - 2
+		// | / | / |
+		// 3 - 4 - 5
+		let mesh = document(&[&[0, 3, 1], &[1, 3, 4], &[1, 4, 2], &[2, 4, 5]])
+			.half_edge_mesh()
+			.unwrap();
+
+		assert_eq!(
+			mesh.half_edges().filter(|&h| mesh.is_boundary(h)).count(),
+			6
+		);
+		assert!(mesh.is_boundary_vertex(VertexRef(4)));
+		assert_eq!(mesh.vertex_half_edges(VertexRef(4)).count(), 3);
+		assert_eq!(mesh.vertex_half_edges(VertexRef(0)).count(), 1);
+
+		let faces = mesh
+			.vertex_half_edges(VertexRef(1))
+			.map(|half_edge| mesh.face(half_edge))
+			.collect::<Vec<_>>();
+		assert_eq!(faces, [0, 1, 2]);
+	}
+
+	#[test]
+	fn invalid() {
+		assert_eq!(
+			document(&[&[0, 1]]).half_edge_mesh(),
+			Err(HalfEdgeError::DegenerateFace(0))
+		);
+		assert_eq!(
+			document(&[&[0, 1, 2], &[0, 1, 1]]).half_edge_mesh(),
+			Err(HalfEdgeError::DegenerateFace(1))
+		);
+		assert_eq!(
+			document(&[&[0, 1, 2], &[1, 0, 3], &[0, 1, 4]]).half_edge_mesh(),
+			Err(HalfEdgeError::NonManifoldEdge(VertexRef(0), VertexRef(1)))
+		);
+		assert_eq!(
+			document(&[&[0, 1, 2], &[0, 1, 3]]).half_edge_mesh(),
+			Err(HalfEdgeError::InconsistentOrientation(
+				VertexRef(0),
+				VertexRef(1)
+			))
+		);
+		// Two triangles which only share a vertex
+		assert_eq!(
+			document(&[&[0, 1, 3], &[1, 2, 4]]).half_edge_mesh(),
+			Err(HalfEdgeError::NonManifoldVertex(VertexRef(1)))
+		);
+	}
+}
@@ -0,0 +1,174 @@
+use std::fmt;
+
+/// Describes the layout and encoding of an OFF document as given by its
+/// magic (e.g. `STCN4nOFF BINARY`) and, for `nOFF`, its dimension line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct OffHeader {
+	/// `ST` prefix; each vertex has texture coordinates after its color.
+	pub texture_coords: bool,
+	/// `C` prefix; each vertex has a color after its normal (or
+	/// coordinates).
+	pub vertex_colors: bool,
+	/// `N` prefix; each vertex has a normal after its coordinates.
+	pub vertex_normals: bool,
+	/// `4` prefix; each vertex has an additional (homogeneous) coordinate.
+	pub homogeneous: bool,
+	/// `n` prefix; the dimension of the vertices is given on the next line.
+	pub n_dimensional: bool,
+	/// `BINARY` after the magic
+	pub binary: bool,
+	/// Dimension of the vertices (excluding the homogeneous coordinate).
+	/// Always `3` if the header is not `n_dimensional`.
+	pub dimension: usize,
+}
+
+impl Default for OffHeader {
+	fn default() -> Self {
+		Self {
+			texture_coords: false,
+			vertex_colors: false,
+			vertex_normals: false,
+			homogeneous: false,
+			n_dimensional: false,
+			binary: false,
+			dimension: 3,
+		}
+	}
+}
+
+impl OffHeader {
+	/// Parses the magic line. Returns `None` if the line contains no magic.
+	///
+	/// The dimension of `nOFF` headers is not part of the magic line and is
+	/// set to `3`.
+	pub fn try_from_magic(line: &str) -> Option<Result<Self, String>> {
+		let mut words = line.split_whitespace();
+		let word = words.next()?;
+		let (prefix, suffix) = word.split_at(word.find("OFF")?);
+
+		if suffix != "OFF" {
+			return Some(Err(format!(
+				"Trailing characters after magic (`{}`)",
+				&suffix[3..]
+			)));
+		}
+
+		let mut header = Self::default();
+		let mut rest = prefix;
+
+		for (flag, part) in [
+			(&mut header.texture_coords, "ST"),
+			(&mut header.vertex_colors, "C"),
+			(&mut header.vertex_normals, "N"),
+			(&mut header.homogeneous, "4"),
+			(&mut header.n_dimensional, "n"),
+		] {
+			if let Some(stripped) = rest.strip_prefix(part) {
+				*flag = true;
+				rest = stripped;
+			}
+		}
+
+		if !rest.is_empty() {
+			return Some(Err(format!(
+				"Unknown or misplaced prefix `{}` in `{}` (expected: \
+				 [ST][C][N][4][n]OFF)",
+				rest, word
+			)));
+		}
+
+		match (words.next(), words.next()) {
+			(None, _) => {}
+			(Some("BINARY"), None) => header.binary = true,
+			(Some(word), None) => {
+				return Some(Err(format!(
+					"Unexpected `{}` after magic (expected: `BINARY`)",
+					word
+				)))
+			}
+			(Some(_), Some(word)) => {
+				return Some(Err(format!(
+					"Trailing characters after magic (`{}`)",
+					word
+				)))
+			}
+		}
+
+		Some(Ok(header))
+	}
+
+	/// Number of coordinates of each vertex (including the homogeneous
+	/// coordinate).
+	pub fn coordinate_count(&self) -> usize {
+		self.dimension + usize::from(self.homogeneous)
+	}
+}
+
+/// Writes the magic line (without the dimension of `nOFF` headers).
+impl fmt::Display for OffHeader {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		for (flag, part) in [
+			(self.texture_coords, "ST"),
+			(self.vertex_colors, "C"),
+			(self.vertex_normals, "N"),
+			(self.homogeneous, "4"),
+			(self.n_dimensional, "n"),
+		] {
+			if flag {
+				f.write_str(part)?;
+			}
+		}
+
+		f.write_str("OFF")?;
+
+		if self.binary {
+			f.write_str(" BINARY")?;
+		}
+
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn magic() {
+		let header = OffHeader::try_from_magic("STC4nOFF BINARY").unwrap();
+
+		assert_eq!(
+			header.unwrap(),
+			OffHeader {
+				texture_coords: true,
+				vertex_colors: true,
+				homogeneous: true,
+				n_dimensional: true,
+				binary: true,
+				..Default::default()
+			}
+		);
+
+		for magic in ["OFF", "NOFF", "STCNOFF", "4OFF BINARY"] {
+			let header = OffHeader::try_from_magic(magic).unwrap().unwrap();
+			assert_eq!(header.to_string(), magic);
+		}
+
+		assert!(OffHeader::try_from_magic("8 6 12").is_none());
+	}
+
+	#[test]
+	fn invalid_magic() {
+		let err = OffHeader::try_from_magic("CXOFF").unwrap().unwrap_err();
+		assert!(err.contains("`X`"), "{}", err);
+
+		let err = OffHeader::try_from_magic("NCOFF").unwrap().unwrap_err();
+		assert!(err.contains("`C`"), "{}", err);
+
+		let err = OffHeader::try_from_magic("OFFS").unwrap().unwrap_err();
+		assert!(err.contains("`S`"), "{}", err);
+
+		let err = OffHeader::try_from_magic("OFF ASCII").unwrap().unwrap_err();
+		assert!(err.contains("`ASCII`"), "{}", err);
+	}
+}
@@ -1,5 +1,5 @@
 use crate::document::{Checked, Document};
-use crate::face::ResolvedFaceRef;
+use crate::face::{FaceRef, ResolvedFaceRef};
 
 pub struct FaceIter<'a> {
 	document: &'a Document<Checked>,
@@ -25,15 +25,77 @@ impl<'a> Iterator for FaceIter<'a> {
 			.map(|fr|
                 // SAFETY: A document with state `Checked` (which is the only
                 // one accepted for this iter) has already verified that all
-                // references can be resolved. It is also immutable sot that
-                // no modifications can be made after the check.
+                // references can be resolved. All edits of checked
+                // documents (see `crate::edit`) keep this guarantee.
                 unsafe {fr.resolve_unchecked(&self.document.vertices)})
 			.collect();
 
-		Some(ResolvedFaceRef { vertices, color: face_ref.color.as_ref() })
+		// SAFETY: Same as for vertices; the number of each vertex attribute
+		// is also checked to match the number of vertices.
+		let (vertex_w, vertex_colors, vertex_normals, texture_coords) = unsafe {
+			(
+				resolve_attributes(
+					face_ref,
+					self.document.vertex_w.as_deref(),
+				),
+				resolve_attributes(
+					face_ref,
+					self.document.vertex_colors.as_deref(),
+				),
+				resolve_attributes(
+					face_ref,
+					self.document.vertex_normals.as_deref(),
+				),
+				resolve_attributes(
+					face_ref,
+					self.document.texture_coords.as_deref(),
+				),
+			)
+		};
+
+		let coordinates = self.document.coordinates.as_ref().map(|store| {
+			face_ref
+				.vertex_refs
+				.iter()
+				.map(|fr| {
+					store
+						.get(fr.0)
+						.expect("Checked document with invalid index")
+				})
+				.collect()
+		});
+
+		Some(ResolvedFaceRef {
+			vertices,
+			coordinates,
+			vertex_w,
+			vertex_colors,
+			vertex_normals,
+			texture_coords,
+			color: face_ref.color.as_ref(),
+			colormap_index: face_ref.colormap_index,
+		})
 	}
 }
 
+/// Resolves the per vertex attributes of all vertices of a face.
+///
+/// # Safety
+///
+/// All vertex references of the face must be in bounds of `items`.
+unsafe fn resolve_attributes<'a, T>(
+	face_ref: &FaceRef,
+	items: Option<&'a [T]>,
+) -> Option<Vec<&'a T>> {
+	items.map(|items| {
+		face_ref
+			.vertex_refs
+			.iter()
+			.map(|fr| fr.resolve_unchecked(items))
+			.collect()
+	})
+}
+
 impl Document<Checked> {
 	pub fn face_iter(&self) -> FaceIter<'_> {
 		FaceIter::new(self)
@@ -1,8 +1,16 @@
+pub mod adjacency;
 pub mod build;
 pub mod color;
 pub mod colorformat;
+pub mod colormap;
 pub mod document;
+pub mod edit;
 pub mod face;
+pub mod halfedge;
+pub mod header;
 pub mod iter;
+pub mod orientation;
 pub mod parse;
+pub mod topology;
 pub mod vertex;
+pub mod write;
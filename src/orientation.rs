@@ -0,0 +1,303 @@
+//! Checking and repairing the winding order of faces.
+//!
+//! Two faces sharing an edge are consistently oriented if they traverse the
+//! edge in opposite directions. Only edges with exactly two faces are taken
+//! into account, edges of more faces have no meaningful orientation.
+
+use std::collections::VecDeque;
+use std::fmt;
+
+use crate::adjacency::Adjacency;
+use crate::document::{Checked, Document};
+use crate::face::FaceRef;
+use crate::vertex::VertexRef;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OrientationError {
+	/// The faces connected to the face (index) can not be oriented
+	/// consistently (e.g. a Möbius strip).
+	NonOrientable(usize),
+}
+
+impl fmt::Display for OrientationError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::NonOrientable(face) => write!(
+				f,
+				"Faces connected to face `{}` are not orientable",
+				face
+			),
+		}
+	}
+}
+
+impl std::error::Error for OrientationError {}
+
+/// Winding to which [`Document::orient_faces`] repairs the faces.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Winding {
+	/// Faces are made consistent with the first face of their connected
+	/// component.
+	#[default]
+	Consistent,
+	/// Same as `Consistent`, but closed components are additionally
+	/// oriented so that their faces point outwards (counter-clockwise when
+	/// seen from outside). Open components are only made consistent.
+	Outward,
+}
+
+/// Whether the face traverses the edge from `a` to `b`.
+fn traverses(face_ref: &FaceRef, a: VertexRef, b: VertexRef) -> bool {
+	face_ref.edges().any(|edge| edge == (a, b))
+}
+
+impl Document<Checked> {
+	/// Edges which both of their faces traverse in the same direction.
+	pub fn inconsistent_edges(&self) -> Vec<(VertexRef, VertexRef)> {
+		self.adjacency()
+			.edges()
+			.filter(|&((a, b), faces)| match faces {
+				[f, g] => {
+					traverses(&self.face_refs[*f], a, b)
+						== traverses(&self.face_refs[*g], a, b)
+				}
+				_ => false,
+			})
+			.map(|(edge, _)| edge)
+			.collect()
+	}
+
+	pub fn is_consistently_oriented(&self) -> bool {
+		self.inconsistent_edges().is_empty()
+	}
+
+	/// Reverses the vertices of faces so that each connected component of
+	/// faces is consistently oriented (see [`Winding`]). Returns the indices
+	/// of the reversed faces.
+	///
+	/// If a component is not orientable, no face is changed at all.
+	pub fn orient_faces(
+		&mut self,
+		winding: Winding,
+	) -> Result<Vec<usize>, OrientationError> {
+		let adjacency = self.adjacency();
+		let mut flips = vec![None; self.face_refs.len()];
+
+		for start in 0..self.face_refs.len() {
+			if flips[start].is_some() {
+				continue;
+			}
+
+			let (component, closed) =
+				self.orient_component(&adjacency, start, &mut flips)?;
+
+			if winding == Winding::Outward && closed {
+				let volume = component
+					.iter()
+					.map(|&face| {
+						let volume = self.signed_volume(face);
+						if flips[face] == Some(true) {
+							-volume
+						} else {
+							volume
+						}
+					})
+					.sum::<f64>();
+
+				if volume < 0.0 {
+					for &face in &component {
+						flips[face] = flips[face].map(|flip: bool| !flip);
+					}
+				}
+			}
+		}
+
+		let flipped = (0..flips.len())
+			.filter(|&face| flips[face] == Some(true))
+			.collect::<Vec<_>>();
+
+		for &face in &flipped {
+			self.face_refs[face].vertex_refs.reverse();
+		}
+
+		Ok(flipped)
+	}
+
+	/// Determines which faces of the component of `start` have to be
+	/// flipped to match `start`. Returns the faces of the component and
+	/// whether it is closed.
+	fn orient_component(
+		&self,
+		adjacency: &Adjacency,
+		start: usize,
+		flips: &mut [Option<bool>],
+	) -> Result<(Vec<usize>, bool), OrientationError> {
+		let mut component = vec![start];
+		let mut queue = VecDeque::from([start]);
+		let mut closed = true;
+		flips[start] = Some(false);
+
+		while let Some(face) = queue.pop_front() {
+			let flip = flips[face].unwrap_or_default();
+
+			for (a, b) in self.face_refs[face].edges().filter(|(a, b)| a != b)
+			{
+				let other = match adjacency.edge_faces(a, b) {
+					[f, g] => {
+						if *f == face {
+							*g
+						} else {
+							*f
+						}
+					}
+					_ => {
+						closed = false;
+						continue;
+					}
+				};
+
+				// Flipped if it traverses the edge in the same direction
+				let other_flip =
+					flip ^ traverses(&self.face_refs[other], a, b);
+
+				match flips[other] {
+					None => {
+						flips[other] = Some(other_flip);
+						component.push(other);
+						queue.push_back(other);
+					}
+					Some(existing) if existing != other_flip => {
+						return Err(OrientationError::NonOrientable(start));
+					}
+					Some(_) => {}
+				}
+			}
+		}
+
+		Ok((component, closed))
+	}
+
+	/// Signed volume of the tetrahedra of the origin and the triangles of the
+	/// face (as fan). The volumes of all faces of a closed mesh add up to its
+	/// volume, which is negative if the faces point inwards.
+	fn signed_volume(&self, face: usize) -> f64 {
+		let vertices = self.face_refs[face]
+			.vertex_refs
+			.iter()
+			.map(|vertex_ref| {
+				let vertex = self.vertices[vertex_ref.0];
+				[vertex.x, vertex.y, vertex.z].map(f64::from)
+			})
+			.collect::<Vec<_>>();
+
+		let first = match vertices.first() {
+			Some(first) => first,
+			None => return 0.0,
+		};
+
+		vertices[1..]
+			.windows(2)
+			.map(|pair| {
+				let [b, c] = [pair[0], pair[1]];
+				let cross = [
+					b[1] * c[2] - b[2] * c[1],
+					b[2] * c[0] - b[0] * c[2],
+					b[0] * c[1] - b[1] * c[0],
+				];
+
+				(first[0] * cross[0]
+					+ first[1] * cross[1]
+					+ first[2] * cross[2])
+					/ 6.0
+			})
+			.sum()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::parse::OffParser;
+	use crate::vertex::Vertex;
+
+	fn cube() -> Document<Checked> {
+		let content = include_str!("../resources/wiki.off");
+		OffParser::new(&content).try_parse().unwrap().validate().unwrap()
+	}
+
+	#[test]
+	fn repair() {
+		let original = cube();
+		assert!(original.is_consistently_oriented());
+
+		let mut document = original.clone();
+		assert_eq!(document.orient_faces(Winding::Outward), Ok(vec![]));
+
+		// Turn two opposite faces around
+		for face in [0, 5] {
+			let mut vertex_refs =
+				document.face_refs()[face].vertex_refs.clone();
+			vertex_refs.reverse();
+			document.set_face_vertices(face, vertex_refs).unwrap();
+		}
+
+		assert_eq!(document.inconsistent_edges().len(), 8);
+
+		// Consistent with the (turned) first face
+		let mut inward = document.clone();
+		assert_eq!(
+			inward.orient_faces(Winding::Consistent),
+			Ok(vec![1, 2, 3, 4])
+		);
+		assert!(inward.is_consistently_oriented());
+
+		assert_eq!(document.orient_faces(Winding::Outward), Ok(vec![0, 5]));
+		let faces =
+			|document: &Document<Checked>| document.face_refs().to_vec();
+		assert_eq!(faces(&document), faces(&original));
+
+		// Inwards facing cube
+		let mut document = original.clone();
+		for face in 0..6 {
+			let mut vertex_refs =
+				document.face_refs()[face].vertex_refs.clone();
+			vertex_refs.reverse();
+			document.set_face_vertices(face, vertex_refs).unwrap();
+		}
+
+		assert_eq!(document.orient_faces(Winding::Consistent), Ok(vec![]));
+		assert_eq!(
+			document.orient_faces(Winding::Outward),
+			Ok((0..6).collect::<Vec<_>>())
+		);
+		assert_eq!(faces(&document), faces(&original));
+	}
+
+	#[test]
+	fn non_orientable() {
+		// Möbius strip of three quads
+		let mut builder = Document::build();
+		let vertices = (0..6)
+			.map(|i| builder.add_vertex(Vertex::new(i as f32, 0.0, 0.0)))
+			.collect::<Vec<_>>();
+
+		for face in [[0, 1, 3, 2], [2, 3, 5, 4], [4, 5, 0, 1]] {
+			builder
+				.add_face(face.iter().map(|&i| vertices[i]).collect(), None)
+				.unwrap();
+		}
+
+		let mut document = builder.finish();
+		let original = document.clone();
+
+		assert_eq!(
+			document.inconsistent_edges(),
+			[(VertexRef(0), VertexRef(1))]
+		);
+		assert_eq!(
+			document.orient_faces(Winding::Consistent),
+			Err(OrientationError::NonOrientable(0))
+		);
+		assert_eq!(document, original);
+	}
+}
@@ -1,17 +1,39 @@
+pub mod binary;
+pub mod diagnostic;
+pub mod event;
+pub mod sink;
+pub mod warning;
+
 use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::io::BufRead;
 use std::iter::{Enumerate, Peekable};
-use std::str::Lines;
+use std::marker::PhantomData;
+use std::ops::Range;
+use std::str::{Lines, SplitWhitespace};
 
+use crate::color::Color;
 use crate::colorformat::ColorFormat;
 use crate::document::{Document, Unchecked};
 use crate::face::FaceRef;
-use crate::vertex::{Vertex, VertexRef};
+use crate::header::OffHeader;
+use crate::parse::diagnostic::Diagnostic;
+use crate::parse::event::{Event, OffEvents};
+use crate::parse::sink::{DocumentSink, MeshSink};
+use crate::parse::warning::{DeniedWarnings, Warning, WarningKind};
+use crate::vertex::{Normal, TextureCoord, Vertex, VertexRef, VertexStore};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Error {
 	kind: ErrorKind,
 	line_index: usize,
 	message: Option<Cow<'static, str>>,
+	/// Bytes of the document which caused the error
+	span: Option<Range<usize>>,
+	/// Column (in characters, not bytes) of the span in its line
+	column: Option<usize>,
+	/// Explanation of how the error could be fixed
+	hint: Option<Cow<'static, str>>,
 }
 
 impl Error {
@@ -20,7 +42,14 @@ impl Error {
 		line_index: usize,
 		message: Option<Cow<'static, str>>,
 	) -> Self {
-		Self { kind, line_index, message }
+		Self {
+			kind,
+			line_index,
+			message,
+			span: None,
+			column: None,
+			hint: None,
+		}
 	}
 
 	pub fn with_message<M: Into<Cow<'static, str>>, O: Into<Option<M>>>(
@@ -28,15 +57,62 @@ impl Error {
 		line_index: usize,
 		message: O,
 	) -> Self {
-		Self {
-			kind,
-			line_index,
-			message: message.into().map(|inner| inner.into()),
-		}
+		Self::new(kind, line_index, message.into().map(|inner| inner.into()))
 	}
 
 	pub fn without_message(kind: ErrorKind, line_index: usize) -> Self {
-		Self { kind, line_index, message: None }
+		Self::new(kind, line_index, None)
+	}
+
+	/// Sets the bytes of the document which caused the error and, if the
+	/// document consists of lines, their column in the line. The column
+	/// counts characters, so that it matches what editors display.
+	#[must_use]
+	pub fn with_span(
+		mut self,
+		span: Range<usize>,
+		column: Option<usize>,
+	) -> Self {
+		self.span = Some(span);
+		self.column = column;
+		self
+	}
+
+	#[must_use]
+	pub fn with_hint<H: Into<Cow<'static, str>>>(mut self, hint: H) -> Self {
+		self.hint = Some(hint.into());
+		self
+	}
+
+	pub fn kind(&self) -> ErrorKind {
+		self.kind
+	}
+
+	pub fn line_index(&self) -> usize {
+		self.line_index
+	}
+
+	pub fn message(&self) -> Option<&str> {
+		self.message.as_deref()
+	}
+
+	pub fn span(&self) -> Option<Range<usize>> {
+		self.span.clone()
+	}
+
+	/// Zero-based column in characters (see [`Error::with_span`]).
+	pub fn column(&self) -> Option<usize> {
+		self.column
+	}
+
+	pub fn hint(&self) -> Option<&str> {
+		self.hint.as_deref()
+	}
+
+	/// Renders the error together with the line of `source` which caused it
+	/// (see [`Diagnostic`]).
+	pub fn diagnostic<'a>(&'a self, source: &'a str) -> Diagnostic<'a> {
+		Diagnostic::new(self, source)
 	}
 }
 
@@ -45,11 +121,17 @@ impl std::fmt::Display for Error {
 		&self,
 		f: &mut std::fmt::Formatter<'_>,
 	) -> Result<(), std::fmt::Error> {
+		write!(f, "{} @ ln:{}", self.kind, self.line_index + 1)?;
+
+		if let Some(column) = self.column {
+			write!(f, ":{}", column + 1)?;
+		}
+
 		if let Some(msg) = &self.message {
-			write!(f, "{} @ ln:{} - {}", self.kind, self.line_index + 1, msg)
-		} else {
-			write!(f, "{} @ ln:{}", self.kind, self.line_index + 1,)
+			write!(f, " - {}", msg)?;
 		}
+
+		Ok(())
 	}
 }
 
@@ -62,10 +144,14 @@ pub enum ErrorKind {
 	Invalid,
 	InvalidMagic,
 	InvalidCounts,
+	InvalidDimension,
 	InvalidVertex,
 	InvalidColor,
 	InvalidFace,
 	LimitExceeded,
+	/// Reading from the underlying stream failed (see
+	/// [`OffReadLines`]).
+	Io,
 }
 
 impl std::fmt::Display for ErrorKind {
@@ -81,12 +167,13 @@ pub type Result<T, E = Error> = std::result::Result<T, E>;
 
 #[derive(Debug, Clone)]
 pub struct OffLines<'a> {
+	source: &'a str,
 	lines: Enumerate<Lines<'a>>,
 }
 
 impl<'a> OffLines<'a> {
 	pub fn new(s: &'a str) -> Self {
-		Self { lines: s.lines().enumerate() }
+		Self { source: s, lines: s.lines().enumerate() }
 	}
 }
 
@@ -94,14 +181,8 @@ impl<'a> Iterator for OffLines<'a> {
 	type Item = (usize, &'a str);
 
 	fn next(&mut self) -> Option<Self::Item> {
-		for (line_index, mut line) in self.lines.by_ref() {
-			if let Some(comment_index) = line.find('#') {
-				line = &line[..comment_index];
-			}
-
-			// Trim after removing comments to prevent the following `Hello # World` => `Hello `
-			// (should be `Hello`)
-			line = line.trim();
+		for (line_index, line) in self.lines.by_ref() {
+			let line = strip_line(line);
 
 			if !line.is_empty() {
 				return Some((line_index, line));
@@ -112,45 +193,596 @@ impl<'a> Iterator for OffLines<'a> {
 	}
 }
 
+/// Removes the comment and surrounding whitespace from a line.
+fn strip_line(mut line: &str) -> &str {
+	if let Some(comment_index) = line.find('#') {
+		line = &line[..comment_index];
+	}
+
+	// Trim after removing comments to prevent the following `Hello # World` => `Hello `
+	// (should be `Hello`)
+	line.trim()
+}
+
+/// Byte offset of `part` in `whole`. `part` must be a sub-slice of `whole`.
+fn offset_in(whole: &str, part: &str) -> usize {
+	let offset = part.as_ptr() as usize - whole.as_ptr() as usize;
+	debug_assert!(offset + part.len() <= whole.len());
+	offset
+}
+
+/// Same as [`OffLines`], but reads the lines one by one from a stream
+/// instead of requiring the whole document in memory.
+#[derive(Debug)]
+pub struct OffReadLines<R> {
+	reader: R,
+	line_index: usize,
+	offset: usize,
+	buffer: String,
+}
+
+impl<R: BufRead> OffReadLines<R> {
+	pub fn new(reader: R) -> Self {
+		Self { reader, line_index: 0, offset: 0, buffer: String::new() }
+	}
+
+	fn next_source_line(
+		&mut self,
+	) -> Option<std::io::Result<SourceLine<'static>>> {
+		loop {
+			self.buffer.clear();
+
+			let len = match self.reader.read_line(&mut self.buffer) {
+				Ok(0) => return None,
+				Ok(len) => len,
+				Err(err) => return Some(Err(err)),
+			};
+
+			let line_index = self.line_index;
+			let line_offset = self.offset;
+			self.line_index += 1;
+			self.offset += len;
+
+			let line = strip_line(&self.buffer);
+
+			if !line.is_empty() {
+				let start = offset_in(&self.buffer, line);
+
+				return Some(Ok(SourceLine {
+					index: line_index,
+					offset: line_offset + start,
+					column: self.buffer[..start].chars().count(),
+					text: line.to_owned().into(),
+				}));
+			}
+		}
+	}
+}
+
+impl<R: BufRead> Iterator for OffReadLines<R> {
+	type Item = std::io::Result<(usize, String)>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		self.next_source_line()
+			.map(|line| line.map(|line| (line.index, line.text.into_owned())))
+	}
+}
+
+/// Non-empty line of a document without its comment.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SourceLine<'a> {
+	pub index: usize,
+	/// Byte offset of `text` in the document
+	pub offset: usize,
+	/// Column (in characters) of `text` in its line
+	pub column: usize,
+	pub text: Cow<'a, str>,
+}
+
+impl SourceLine<'_> {
+	/// Error located at `token`, which must be a part of `text`.
+	fn error_at<M: Into<Cow<'static, str>>>(
+		&self,
+		kind: ErrorKind,
+		token: &str,
+		message: M,
+	) -> Error {
+		let start = offset_in(&self.text, token);
+
+		Error::with_message(kind, self.index, message).with_span(
+			self.offset + start..self.offset + start + token.len(),
+			Some(self.column + self.text[..start].chars().count()),
+		)
+	}
+
+	/// Error located at `token` and everything after it.
+	fn error_from<M: Into<Cow<'static, str>>>(
+		&self,
+		kind: ErrorKind,
+		token: &str,
+		message: M,
+	) -> Error {
+		let start = offset_in(&self.text, token);
+		self.error_at(kind, &self.text[start..], message)
+	}
+
+	/// Error located at the end of the line (e.g. for missing elements).
+	fn error_at_end<M: Into<Cow<'static, str>>>(
+		&self,
+		kind: ErrorKind,
+		message: M,
+	) -> Error {
+		self.error_at(kind, &self.text[self.text.len()..], message)
+	}
+
+	/// Error located at the whole line.
+	fn error<M: Into<Cow<'static, str>>>(
+		&self,
+		kind: ErrorKind,
+		message: M,
+	) -> Error {
+		self.error_at(kind, &self.text, message)
+	}
+}
+
+/// Lines from which an [`OffParser`] can parse a document. Yields the
+/// non-empty lines without comments.
+pub trait LineSource<'a> {
+	fn next_line(&mut self) -> Result<Option<SourceLine<'a>>>;
+}
+
+impl<'a> LineSource<'a> for OffLines<'a> {
+	fn next_line(&mut self) -> Result<Option<SourceLine<'a>>> {
+		Ok(self.next().map(|(index, text)| {
+			let offset = offset_in(self.source, text);
+			let line_start =
+				self.source[..offset].rfind('\n').map_or(0, |i| i + 1);
+
+			SourceLine {
+				index,
+				offset,
+				column: self.source[line_start..offset].chars().count(),
+				text: text.into(),
+			}
+		}))
+	}
+}
+
+impl<'a, R: BufRead> LineSource<'a> for OffReadLines<R> {
+	fn next_line(&mut self) -> Result<Option<SourceLine<'a>>> {
+		let line_index = self.line_index;
+
+		self.next_source_line().transpose().map_err(|err| {
+			Error::with_message(ErrorKind::Io, line_index, err.to_string())
+		})
+	}
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Limits {
 	pub vertex_count: usize,
 	pub face_count: usize,
 	pub face_vertex_count: usize,
+	/// Maximum dimension of `nOFF` vertices
+	pub dimension: usize,
 }
 
 impl Default for Limits {
 	fn default() -> Self {
-		Self { vertex_count: 2048, face_count: 4096, face_vertex_count: 128 }
+		Self {
+			vertex_count: 2048,
+			face_count: 4096,
+			face_vertex_count: 128,
+			dimension: 32,
+		}
 	}
 }
 
+/// How the homogeneous component `w` of `4OFF` vertices is handled.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Homogeneous {
+	/// Keep `w` as a separate vertex attribute
+	/// (see [`Document::vertex_w`](crate::document::Document::vertex_w)).
+	#[default]
+	Keep,
+	/// Divide all other coordinates by `w`. No vertex attribute is kept.
+	Project,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct ParserOptions<C> {
 	pub color_format: C,
 	pub limits: Limits,
+	pub homogeneous: Homogeneous,
+	/// Warning kinds which are reported as errors
+	pub denied_warnings: DeniedWarnings,
+}
+
+impl<C> ParserOptions<C> {
+	/// Options for the color format with all other options at their
+	/// defaults.
+	pub fn with_color_format(color_format: C) -> Self {
+		Self {
+			color_format,
+			limits: Default::default(),
+			homogeneous: Default::default(),
+			denied_warnings: Default::default(),
+		}
+	}
 }
 
 impl Default for ParserOptions<crate::colorformat::Any> {
 	fn default() -> Self {
+		Self::with_color_format(crate::colorformat::Any)
+	}
+}
+
+/// Attributes of a single vertex in addition to its position.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct VertexAttributes {
+	/// All coordinates of `nOFF` vertices
+	pub coordinates: Option<Vec<f32>>,
+	pub w: Option<f32>,
+	pub color: Option<Color>,
+	pub normal: Option<Normal>,
+	pub texture_coord: Option<TextureCoord>,
+}
+
+/// Collects vertices and their attributes while parsing.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(crate) struct Vertices {
+	header: OffHeader,
+	vertices: Vec<Vertex>,
+	coordinates: Option<VertexStore>,
+	w: Vec<f32>,
+	colors: Vec<Color>,
+	normals: Vec<Normal>,
+	texture_coords: Vec<TextureCoord>,
+}
+
+impl Vertices {
+	pub(crate) fn with_capacity(
+		header: &OffHeader,
+		vertex_count: usize,
+	) -> Self {
 		Self {
-			color_format: crate::colorformat::Any,
-			limits: Default::default(),
+			header: *header,
+			vertices: Vec::with_capacity(vertex_count),
+			coordinates: header.n_dimensional.then(|| {
+				VertexStore::with_capacity(header.dimension, vertex_count)
+			}),
+			..Default::default()
+		}
+	}
+
+	pub(crate) fn push(
+		&mut self,
+		vertex: Vertex,
+		attributes: VertexAttributes,
+	) {
+		// `nOFF` vertices are derived from the store by the document
+		match (&mut self.coordinates, attributes.coordinates) {
+			(Some(store), Some(coordinates)) => store.push(&coordinates),
+			_ => self.vertices.push(vertex),
+		}
+
+		self.w.extend(attributes.w);
+		self.colors.extend(attributes.color);
+		self.normals.extend(attributes.normal);
+		self.texture_coords.extend(attributes.texture_coord);
+	}
+
+	pub(crate) fn push_color(&mut self, color: Color) {
+		self.colors.push(color);
+	}
+
+	pub(crate) fn into_document(
+		self,
+		face_refs: Vec<FaceRef>,
+		edge_count: Option<u64>,
+	) -> Document<Unchecked> {
+		let vertex_count = self
+			.coordinates
+			.as_ref()
+			.map_or(self.vertices.len(), VertexStore::len);
+
+		// The color format `None` skips the colors
+		let colors = (self.header.vertex_colors
+			&& self.colors.len() == vertex_count)
+			.then_some(self.colors);
+		// Projected vertices have no `w`
+		let w = (self.header.homogeneous && self.w.len() == vertex_count)
+			.then_some(self.w);
+		let normals = self.header.vertex_normals.then_some(self.normals);
+		let texture_coords =
+			self.header.texture_coords.then_some(self.texture_coords);
+
+		Document::new(self.vertices, face_refs, edge_count)
+			.with_header(self.header)
+			.with_coordinates(self.coordinates)
+			.with_vertex_w(w)
+			.with_vertex_colors(colors)
+			.with_vertex_normals(normals)
+			.with_texture_coords(texture_coords)
+	}
+}
+
+/// Creates a vertex from all coordinates of a vertex line (including `w`).
+///
+/// Depending on `mode` `w` is either stored in `attributes` or used to
+/// project the vertex. For `nOFF` vertices all coordinates are stored in
+/// `attributes` and the vertex consists of the first three coordinates.
+pub(crate) fn vertex_from_coordinates(
+	coordinates: &mut [f32],
+	header: &OffHeader,
+	mode: Homogeneous,
+	attributes: &mut VertexAttributes,
+) -> Result<Vertex, &'static str> {
+	let coordinates = match coordinates.split_last_mut() {
+		Some((&mut w, coordinates)) if header.homogeneous => {
+			match mode {
+				Homogeneous::Keep => attributes.w = Some(w),
+				Homogeneous::Project if w == 0.0 => {
+					return Err("Can not project vertex at infinity (w = 0)")
+				}
+				Homogeneous::Project => {
+					coordinates.iter_mut().for_each(|c| *c /= w)
+				}
+			}
+
+			coordinates
+		}
+		_ => coordinates,
+	};
+
+	let vertex = Vertex::from_coordinates(coordinates);
+
+	if header.n_dimensional {
+		attributes.coordinates = Some(coordinates.to_vec());
+	}
+
+	Ok(vertex)
+}
+
+/// Checks the dimension of `nOFF` vertices against the limits and the other
+/// attributes.
+pub(crate) fn check_dimension(
+	dimension: usize,
+	header: &OffHeader,
+	limits: &Limits,
+) -> Result<(), (ErrorKind, String)> {
+	if dimension == 0 {
+		Err((ErrorKind::InvalidDimension, "Dimension must not be 0".into()))
+	} else if dimension > limits.dimension {
+		Err((
+			ErrorKind::LimitExceeded,
+			format!(
+				"Dimension exceeds limit (limit: {}, requested: {})",
+				limits.dimension, dimension
+			),
+		))
+	} else if header.vertex_normals && dimension != 3 {
+		Err((
+			ErrorKind::InvalidDimension,
+			format!(
+				"Normals are only supported for 3 dimensional vertices (got: \
+				 {})",
+				dimension
+			),
+		))
+	} else {
+		Ok(())
+	}
+}
+
+/// Source of the header of a document. The text and the binary parser only
+/// differ in the encoding, the header is read by [`parse_header`] for both.
+pub(crate) trait HeaderSource {
+	fn cached_header(&mut self) -> &mut Option<OffHeader>;
+
+	fn limits(&self) -> &Limits;
+
+	fn try_consume_magic(&mut self) -> Result<OffHeader>;
+
+	/// Consumes the dimension of `nOFF` vertices. Errors of `check` are
+	/// located at the dimension.
+	fn try_consume_dimension(
+		&mut self,
+		check: impl FnOnce(usize) -> Result<(), (ErrorKind, String)>,
+	) -> Result<usize>;
+}
+
+/// Parses the header (magic and dimension) and caches it, so that it is only
+/// parsed once.
+pub(crate) fn parse_header(
+	source: &mut impl HeaderSource,
+) -> Result<OffHeader> {
+	if let Some(header) = *source.cached_header() {
+		return Ok(header);
+	}
+
+	let mut header = source.try_consume_magic()?;
+
+	if header.n_dimensional {
+		let limits = *source.limits();
+		header.dimension = source.try_consume_dimension(|dimension| {
+			check_dimension(dimension, &header, &limits)
+		})?;
+	}
+
+	*source.cached_header() = Some(header);
+
+	Ok(header)
+}
+
+/// Elements of a single vertex, read by [`parse_vertex`].
+pub(crate) trait VertexElements {
+	/// Consumes the next `values.len()` floats, which are called `name` in
+	/// errors.
+	fn try_consume_floats(
+		&mut self,
+		name: &'static str,
+		values: &mut [f32],
+	) -> Result<()>;
+
+	/// Consumes the color of the vertex. Formats without elements skip it.
+	fn try_consume_color(
+		&mut self,
+		header: &OffHeader,
+	) -> Result<Option<Color>>;
+
+	/// Error for the vertex as a whole.
+	fn error(&self, message: &'static str) -> Error;
+}
+
+/// Parses a vertex with all attributes given by the header.
+pub(crate) fn parse_vertex(
+	elements: &mut impl VertexElements,
+	header: &OffHeader,
+	homogeneous: Homogeneous,
+) -> Result<(Vertex, VertexAttributes)> {
+	let mut attributes = VertexAttributes::default();
+
+	let coordinate_count = header.coordinate_count();
+	// Only allocate for `nOFF`, the other variants have at most 4
+	let mut buffer = [0.0; 4];
+	let mut owned;
+	let coordinates = if header.n_dimensional {
+		owned = vec![0.0; coordinate_count];
+		&mut owned[..]
+	} else {
+		&mut buffer[..coordinate_count]
+	};
+
+	elements.try_consume_floats("coordinates", coordinates)?;
+
+	let vertex = vertex_from_coordinates(
+		coordinates,
+		header,
+		homogeneous,
+		&mut attributes,
+	)
+	.map_err(|msg| elements.error(msg))?;
+
+	if header.vertex_normals {
+		let mut normal = [0.0; 3];
+		elements.try_consume_floats("normal elements", &mut normal)?;
+		let [x, y, z] = normal;
+		attributes.normal = Some(Normal::new(x, y, z));
+	}
+
+	attributes.color = elements.try_consume_color(header)?;
+
+	if header.texture_coords {
+		let mut texture_coord = [0.0; 2];
+		elements
+			.try_consume_floats("texture coordinates", &mut texture_coord)?;
+		let [s, t] = texture_coord;
+		attributes.texture_coord = Some(TextureCoord::new(s, t));
+	}
+
+	Ok((vertex, attributes))
+}
+
+/// Passes a single event to the sink.
+fn send_event<S: MeshSink>(sink: &mut S, event: Event) {
+	match event {
+		Event::Header(header) => sink.header(&header),
+		Event::Counts { vertex_count, face_count, edge_count } => {
+			sink.counts(vertex_count, face_count, edge_count)
+		}
+		Event::Vertex(index, vertex, mut attributes) => {
+			let color = attributes.color.take();
+
+			sink.vertex(index, vertex, attributes);
+
+			if let Some(color) = color {
+				sink.vertex_color(index, color);
+			}
 		}
+		Event::Face(index, face) => {
+			let color = face.face_color();
+
+			sink.face(index, face.vertex_refs);
+
+			if let Some(color) = color {
+				sink.face_color(index, color);
+			}
+		}
+		Event::Warning(_) | Event::End => {}
 	}
 }
 
-pub struct OffParser<'a, C> {
-	#[allow(unused)]
+/// Vertex at the origin which replaces a malformed vertex while recovering.
+///
+/// It has all attributes given by the header (zeroed, `w` of `1` and an
+/// opaque black color) so that the attributes of the other vertices are
+/// kept.
+pub fn placeholder_vertex<C: ColorFormat>(
+	header: &OffHeader,
+	homogeneous: Homogeneous,
+) -> (Vertex, VertexAttributes) {
+	let attributes = VertexAttributes {
+		coordinates: header.n_dimensional.then(|| vec![0.0; header.dimension]),
+		w: (header.homogeneous && homogeneous == Homogeneous::Keep)
+			.then_some(1.0),
+		color: (header.vertex_colors && C::HAS_ELEMENTS)
+			.then(|| Color::new(0, 0, 0, 255)),
+		normal: header.vertex_normals.then(|| Normal::new(0.0, 0.0, 0.0)),
+		texture_coord: header
+			.texture_coords
+			.then(|| TextureCoord::new(0.0, 0.0)),
+	};
+
+	(Vertex::new(0.0, 0.0, 0.0), attributes)
+}
+
+/// Parses the next `values.len()` words as floats.
+fn try_parse_floats_into<'w>(
+	words: &mut impl Iterator<Item = &'w str>,
+	line: &SourceLine<'_>,
+	name: &str,
+	values: &mut [f32],
+) -> Result<()> {
+	let expected = values.len();
+
+	for (i, value) in values.iter_mut().enumerate() {
+		let word = words.next().ok_or_else(|| {
+			line.error_at_end(
+				ErrorKind::InvalidVertex,
+				format!(
+					"Invalid number of {} given (expected: {}, actual: {})",
+					name, expected, i
+				),
+			)
+		})?;
+
+		*value = word.parse().map_err(|err| {
+			line.error_at(
+				ErrorKind::InvalidVertex,
+				word,
+				format!("Failed to parse {} as number ({})", name, err),
+			)
+		})?;
+	}
+
+	Ok(())
+}
+
+pub struct OffParser<'a, C, L = OffLines<'a>> {
 	options: ParserOptions<C>,
-	lines: Peekable<OffLines<'a>>,
+	lines: L,
+	peeked: Option<SourceLine<'a>>,
+	/// Index of the line after the last consumed one
+	line_index: usize,
+	header: Option<OffHeader>,
+	/// Warnings which were not yet emitted as events
+	warnings: VecDeque<Warning>,
 }
 
 impl<'a> OffParser<'a, crate::colorformat::Any> {
 	pub fn new<S: AsRef<str>>(s: &'a S) -> Self {
-		let lines = OffLines::new(s.as_ref()).peekable();
-
-		Self { lines, options: Default::default() }
+		Self::new_with_options(s, Default::default())
 	}
 }
 
@@ -162,188 +794,353 @@ where
 		s: &'a S,
 		options: ParserOptions<C>,
 	) -> Self {
-		let lines = OffLines::new(s.as_ref()).peekable();
+		Self::from_lines(OffLines::new(s.as_ref()), options)
+	}
+}
 
-		Self { lines, options }
+impl<R: BufRead> OffParser<'static, crate::colorformat::Any, OffReadLines<R>> {
+	/// Parses the document line by line from `reader`.
+	pub fn from_reader(reader: R) -> Self {
+		Self::from_reader_with_options(reader, Default::default())
 	}
+}
 
-	pub fn try_parse(mut self) -> Result<Document<Unchecked>> {
-		let _ = self.try_consume_magic()?;
-		let (vertex_count, face_count, edge_count) =
-			self.try_consume_counts()?;
+impl<R: BufRead, C> OffParser<'static, C, OffReadLines<R>>
+where
+	C: ColorFormat,
+{
+	pub fn from_reader_with_options(
+		reader: R,
+		options: ParserOptions<C>,
+	) -> Self {
+		Self::from_lines(OffReadLines::new(reader), options)
+	}
+}
 
-		if vertex_count > self.options.limits.vertex_count {
-			return Err(Error::with_message(
-				ErrorKind::LimitExceeded,
-				0, // TODO: save last line index
-				format!(
-					"Vertex count exceeds limit (limit: {}, requested: {})",
-					self.options.limits.vertex_count, vertex_count
-				),
-			));
+impl<'a, C, L> OffParser<'a, C, L>
+where
+	C: ColorFormat,
+	L: LineSource<'a>,
+{
+	pub fn from_lines(lines: L, options: ParserOptions<C>) -> Self {
+		Self {
+			options,
+			lines,
+			peeked: None,
+			line_index: 0,
+			header: None,
+			warnings: VecDeque::new(),
 		}
+	}
 
-		if face_count > self.options.limits.face_count {
-			return Err(Error::with_message(
-				ErrorKind::LimitExceeded,
-				0, // TODO: save last line index
-				format!(
-					"Face count exceeds limit (limit: {}, requested: {})",
-					self.options.limits.face_count, face_count
-				),
-			));
+	/// Parses the header (magic and dimension) without parsing the rest of
+	/// the document. The header is cached for a subsequent call to
+	/// [`OffParser::try_parse`].
+	pub fn try_parse_header(&mut self) -> Result<OffHeader> {
+		parse_header(self)
+	}
+
+	/// Turns the parser into an iterator of parse [`Event`]s.
+	pub fn into_events(self) -> OffEvents<'a, C, L> {
+		OffEvents::new(self)
+	}
+
+	pub fn try_parse(self) -> Result<Document<Unchecked>> {
+		self.try_parse_into(DocumentSink::default())
+	}
+
+	/// Parses the document into `sink`. The output of the sink is only
+	/// returned if the whole document could be parsed.
+	pub fn try_parse_into<S: MeshSink>(
+		self,
+		mut sink: S,
+	) -> Result<S::Output> {
+		for event in self.into_events() {
+			send_event(&mut sink, event?);
 		}
 
-		let vertices = self.try_consume_vertices(vertex_count)?;
-		let faces = self.try_consume_faces(face_count, vertex_count)?;
+		Ok(sink.finish())
+	}
+
+	/// Same as [`OffParser::try_parse`], but also returns the warnings for
+	/// the document. Warnings of kinds which are denied by
+	/// [`ParserOptions::denied_warnings`] are returned as errors instead.
+	///
+	/// Only this variant checks the declared edge count (see
+	/// [`OffEvents::checking_edge_count`]), unless the warning is denied.
+	pub fn try_parse_with_warnings(
+		self,
+	) -> Result<(Document<Unchecked>, Vec<Warning>)> {
+		self.try_parse_into_with_warnings(DocumentSink::default())
+	}
+
+	/// Variant of [`OffParser::try_parse_into`] which also returns the
+	/// warnings (see [`OffParser::try_parse_with_warnings`]).
+	pub fn try_parse_into_with_warnings<S: MeshSink>(
+		self,
+		mut sink: S,
+	) -> Result<(S::Output, Vec<Warning>)> {
+		let mut warnings = Vec::new();
+
+		for event in self.into_events().checking_edge_count() {
+			match event? {
+				Event::Warning(warning) => warnings.push(warning),
+				event => send_event(&mut sink, event),
+			}
+		}
+
+		Ok((sink.finish(), warnings))
+	}
+
+	/// Same as [`OffParser::try_parse`], but malformed vertex and face lines
+	/// do not stop the parser (see [`OffEvents::recovering`]). All their
+	/// errors are returned together with the document.
+	///
+	/// Malformed vertices are replaced by a placeholder at the origin (see
+	/// [`placeholder_vertex`]), so that the indices of the other vertices
+	/// stay valid. Malformed faces are left out. The line of each problem
+	/// is given by the line index of its error.
+	///
+	/// An error is only returned if no document can be created at all (e.g.
+	/// the magic or counts are invalid). Warnings are not returned, unless
+	/// they are denied and thereby errors.
+	pub fn try_parse_recovering(
+		self,
+	) -> Result<(Document<Unchecked>, Vec<Error>)> {
+		self.try_parse_into_recovering(DocumentSink::default())
+	}
+
+	/// Recovering variant of [`OffParser::try_parse_into`] (see
+	/// [`OffParser::try_parse_recovering`]).
+	pub fn try_parse_into_recovering<S: MeshSink>(
+		self,
+		mut sink: S,
+	) -> Result<(S::Output, Vec<Error>)> {
+		let homogeneous = self.options.homogeneous;
+		let mut header = OffHeader::default();
+		let mut vertex_count = None;
+		let mut next_vertex = 0;
+		let mut errors = Vec::new();
+
+		let fill_vertices =
+			|sink: &mut S, header: &OffHeader, next: &mut usize, end| {
+				for index in *next..end {
+					let (vertex, attributes) =
+						placeholder_vertex::<C>(header, homogeneous);
+					send_event(sink, Event::Vertex(index, vertex, attributes));
+				}
+
+				*next = (*next).max(end);
+			};
+
+		for event in self.into_events().recovering() {
+			let event = match (event, vertex_count) {
+				(Ok(event), _) => event,
+				// Nothing to recover before the counts are known
+				(Err(err), None) => return Err(err),
+				(Err(err), Some(_)) => {
+					errors.push(err);
+					continue;
+				}
+			};
+
+			match (&event, vertex_count) {
+				(Event::Header(parsed), _) => header = *parsed,
+				(Event::Warning(_), _) => continue,
+				(Event::Counts { vertex_count: count, .. }, _) => {
+					vertex_count = Some(*count)
+				}
+				(Event::Vertex(index, ..), _) => {
+					fill_vertices(
+						&mut sink,
+						&header,
+						&mut next_vertex,
+						*index,
+					);
+					next_vertex += 1;
+				}
+				(_, Some(count)) => {
+					fill_vertices(&mut sink, &header, &mut next_vertex, count)
+				}
+				(_, None) => {}
+			}
+
+			send_event(&mut sink, event);
+		}
+
+		// The iteration may have ended early (e.g. missing lines)
+		fill_vertices(
+			&mut sink,
+			&header,
+			&mut next_vertex,
+			vertex_count.unwrap_or(0),
+		);
+
+		Ok((sink.finish(), errors))
+	}
 
-		if let Some((line_index, _)) = self.lines.next() {
-			Err(Error::with_message(
+	fn try_consume_end(&mut self) -> Result<()> {
+		if let Some(line) = self.next_line()? {
+			Err(line.error(
 				ErrorKind::Invalid,
-				line_index,
 				"Unexpected lines after OFF definition",
 			))
 		} else {
-			Ok(Document::new(vertices, faces, edge_count))
+			Ok(())
 		}
 	}
 
-	fn try_consume_magic(&mut self) -> Result<()> {
-		let (line_index, line) = self
-			.lines
-			.peek()
-			.ok_or_else(|| Error::without_message(ErrorKind::Empty, 0))?;
+	fn next_line(&mut self) -> Result<Option<SourceLine<'a>>> {
+		let line = match self.peeked.take() {
+			Some(line) => Some(line),
+			None => self.lines.next_line()?,
+		};
 
-		if let Some(suffix) = line.strip_prefix("OFF") {
-			if suffix.is_empty() {
-				// valid magic
-				// consume peeked item
-				let _ = self.lines.next().expect("Next item not present");
-			} else {
-				// trailing characters; invalid magic
-				return Err(Error::with_message(
-					ErrorKind::InvalidMagic,
-					*line_index,
-					"Trailing characters after magic",
-				));
-			}
+		if let Some(line) = &line {
+			self.line_index = line.index + 1;
 		}
 
-		Ok(())
+		Ok(line)
 	}
 
-	fn try_consume_counts(&mut self) -> Result<(usize, usize, Option<u64>)> {
-		let (line_index, line) = self.lines.next().ok_or_else(|| {
-			Error::with_message(
-				ErrorKind::Missing,
-				0,
-				"No counts for vertices, faces and edges present",
-			)
-		})?;
+	fn peek_line(&mut self) -> Result<Option<&SourceLine<'a>>> {
+		if self.peeked.is_none() {
+			self.peeked = self.lines.next_line()?;
+		}
 
-		let counts = line
-			.split_whitespace()
-			.map(|w| w.parse::<usize>())
-			// Take one more than we expect/want so that we can check bellow
-			// if we got the expected amount or more.
-			.take(4)
-			.collect::<Result<Vec<usize>, _>>()
-			.map_err(|err| {
-				Error::with_message(
-					ErrorKind::InvalidCounts,
-					line_index,
-					format!("Failed to parse count as number ({})", err),
-				)
-			})?;
+		Ok(self.peeked.as_ref())
+	}
 
-		match counts[..] {
-			[vertices, faces, edges] => {
-				Ok((vertices, faces, Some(edges as u64)))
-			}
-			[vertices, faces] => Ok((vertices, faces, None)),
-			_ => Err(Error::with_message(
-				ErrorKind::InvalidCounts,
-				line_index,
-				format!(
-					"Invalid number of counts given (expected: 2-3, actual: \
-					 {})",
-					counts.len()
-				),
-			)),
+	/// Queues a warning to be emitted, or returns it as error if its kind is
+	/// denied.
+	fn warn(&mut self, warning: Warning) -> Result<()> {
+		if self.options.denied_warnings.contains(warning.kind()) {
+			Err(warning.into_error())
+		} else {
+			self.warnings.push_back(warning);
+			Ok(())
 		}
 	}
 
-	fn try_consume_vertices(
-		&mut self,
-		vertex_count: usize,
-	) -> Result<Vec<Vertex>> {
-		(0..vertex_count).map(|_| self.try_consume_vertex()).collect()
+	/// Error for a line which is missing at the end of the document.
+	fn missing(&self, message: &'static str) -> Error {
+		Error::with_message(ErrorKind::Missing, self.line_index, message)
 	}
 
-	fn try_consume_vertex(&mut self) -> Result<Vertex> {
-		let (line_index, line) = self.lines.next().ok_or_else(|| {
-			Error::with_message(ErrorKind::Missing, 0, "Expected vertex")
+	fn try_consume_counts(&mut self) -> Result<(usize, usize, Option<u64>)> {
+		let line = self.next_line()?.ok_or_else(|| {
+			self.missing("No counts for vertices, faces and edges present")
 		})?;
 
-		let coords = line
-			.split_whitespace()
-			.map(|w| w.parse::<f32>())
-			// Take one more than we expect/want so that we can check bellow
-			// if we got the expected amount or more.
-			.take(4)
-			.collect::<Result<Vec<f32>, _>>()
-			.map_err(|err| {
-				Error::with_message(
-					ErrorKind::InvalidVertex,
-					line_index,
-					format!("Failed to parse coordinate as number ({})", err),
+		// Take one more than we expect/want so that we can check bellow if we
+		// got the expected amount or more.
+		let words = line.text.split_whitespace().take(4).collect::<Vec<_>>();
+
+		let counts = words
+			.iter()
+			.map(|word| {
+				word.parse::<usize>().map_err(|err| {
+					line.error_at(
+						ErrorKind::InvalidCounts,
+						word,
+						format!("Failed to parse count as number ({})", err),
+					)
+				})
+			})
+			.collect::<Result<Vec<usize>>>()?;
+
+		let (vertex_count, face_count, edge_count) = match counts[..] {
+			[vertices, faces, edges] => (vertices, faces, Some(edges as u64)),
+			[vertices, faces] => (vertices, faces, None),
+			_ => {
+				return Err(line
+					.error(
+						ErrorKind::InvalidCounts,
+						format!(
+							"Invalid number of counts given (expected: 2-3, \
+							 actual: {})",
+							counts.len()
+						),
+					)
+					.with_hint(
+						"counts are given as `vertices faces [edges]`",
+					))
+			}
+		};
+
+		if vertex_count > self.options.limits.vertex_count {
+			return Err(line
+				.error_at(
+					ErrorKind::LimitExceeded,
+					words[0],
+					format!(
+						"Vertex count exceeds limit (limit: {}, requested: \
+						 {})",
+						self.options.limits.vertex_count, vertex_count
+					),
 				)
-			})?;
+				.with_hint(LIMIT_HINT));
+		}
 
-		if let [x, y, z] = coords[..] {
-			Ok(Vertex::new(x, y, z))
-		} else {
-			Err(Error::with_message(
-				ErrorKind::InvalidVertex,
-				line_index,
-				format!(
-					"Invalid number of coordinates given (expected: 3, \
-					 actual: {})",
-					coords.len()
-				),
-			))
+		if face_count > self.options.limits.face_count {
+			return Err(line
+				.error_at(
+					ErrorKind::LimitExceeded,
+					words[1],
+					format!(
+						"Face count exceeds limit (limit: {}, requested: {})",
+						self.options.limits.face_count, face_count
+					),
+				)
+				.with_hint(LIMIT_HINT));
 		}
+
+		Ok((vertex_count, face_count, edge_count))
 	}
 
-	fn try_consume_faces(
+	fn try_consume_vertex(
 		&mut self,
-		face_count: usize,
-		vertex_count: usize,
-	) -> Result<Vec<FaceRef>> {
-		(0..face_count).map(|_| self.try_consume_face(vertex_count)).collect()
+		header: &OffHeader,
+	) -> Result<(Vertex, VertexAttributes)> {
+		let line = self
+			.next_line()?
+			.ok_or_else(|| self.missing("Expected vertex"))?;
+
+		let mut words = VertexWords::<C>::new(&line);
+		let (vertex, attributes) =
+			parse_vertex(&mut words, header, self.options.homogeneous)?;
+
+		if let Some(word) = words.words.next() {
+			Err(line
+				.error_from(
+					ErrorKind::InvalidVertex,
+					word,
+					"Found elements after vertex definition",
+				)
+				.with_hint(vertex_layout_hint(header)))
+		} else {
+			Ok((vertex, attributes))
+		}
 	}
 
 	fn try_consume_face(&mut self, vertex_count: usize) -> Result<FaceRef> {
-		let (line_index, line) = self.lines.next().ok_or_else(|| {
-			Error::with_message(ErrorKind::Missing, 0, "Expected face")
-		})?;
+		let line =
+			self.next_line()?.ok_or_else(|| self.missing("Expected face"))?;
 
-		let mut words = line.split_whitespace();
+		let mut words = line.text.split_whitespace();
 
-		let vertex_index_count = words
-			.next()
-			.ok_or_else(|| {
-				Error::with_message(
-					ErrorKind::InvalidFace,
-					line_index,
-					"Expected number of vertices",
-				)
-			})?
-			.parse::<usize>()
-			.map_err(|err| {
-				Error::with_message(
+		let count_word = words.next().ok_or_else(|| {
+			line.error_at_end(
+				ErrorKind::InvalidFace,
+				"Expected number of vertices",
+			)
+		})?;
+
+		let vertex_index_count =
+			count_word.parse::<usize>().map_err(|err| {
+				line.error_at(
 					ErrorKind::InvalidFace,
-					line_index,
+					count_word,
 					format!(
 						"Failed to parse vertex count as number ({})",
 						err
@@ -352,52 +1149,100 @@ where
 			})?;
 
 		if vertex_index_count > self.options.limits.face_vertex_count {
-			return Err(Error::with_message(
-				ErrorKind::LimitExceeded,
-				line_index,
-				format!(
-					"Vertex count of face exceeds limit (limit: {}, \
-					 requested: {})",
-					self.options.limits.face_vertex_count, vertex_index_count
+			return Err(line
+				.error_at(
+					ErrorKind::LimitExceeded,
+					count_word,
+					format!(
+						"Vertex count of face exceeds limit (limit: {}, \
+						 requested: {})",
+						self.options.limits.face_vertex_count,
+						vertex_index_count
+					),
+				)
+				.with_hint(LIMIT_HINT));
+		}
+
+		// Only emitted if the face is valid otherwise
+		let mut warnings = Vec::new();
+
+		if vertex_index_count < 3 {
+			warnings.push(Warning::new(
+				WarningKind::DegenerateFace,
+				line.error_at(
+					ErrorKind::InvalidFace,
+					count_word,
+					format!(
+						"Face has less than three vertices ({})",
+						vertex_index_count
+					),
 				),
 			));
 		}
 
 		let mut vertex_indexes = Vec::with_capacity(vertex_index_count);
+		let mut duplicate = false;
 
 		for i in 0..vertex_index_count {
-			let vertex_index = words
-				.next()
-				.ok_or_else(|| {
-					Error::with_message(
+			let word = words.next().ok_or_else(|| {
+				line.error_at_end(
+					ErrorKind::InvalidFace,
+					format!(
+						"Expected vertex index ({}/{})",
+						i, vertex_index_count
+					),
+				)
+				.with_hint(format!(
+					"face declares {} vertices but lists {}",
+					vertex_index_count, i
+				))
+			})?;
+
+			let vertex_index = word.parse::<usize>().map_err(|err| {
+				line.error_at(
+					ErrorKind::InvalidFace,
+					word,
+					format!(
+						"Failed to parse vertex index as number ({}/{}; {})",
+						i, vertex_index_count, err
+					),
+				)
+			})?;
+
+			if vertex_index >= vertex_count {
+				let hint = match vertex_count {
+					0 => "the document declares no vertices".into(),
+					1 => "the only valid vertex index is 0".into(),
+					_ => format!(
+						"valid vertex indices are 0 to {}",
+						vertex_count - 1
+					),
+				};
+
+				return Err(line
+					.error_at(
 						ErrorKind::InvalidFace,
-						line_index,
+						word,
 						format!(
-							"Expected vertex index ({}/{})",
-							i, vertex_index_count
+							"Vertex index out of bounds ({}/{})",
+							vertex_index, vertex_count
 						),
 					)
-				})?
-				.parse::<usize>()
-				.map_err(|err| {
-					Error::with_message(
+					.with_hint(hint));
+			}
+
+			if !duplicate && vertex_indexes.contains(&VertexRef(vertex_index))
+			{
+				duplicate = true;
+				warnings.push(Warning::new(
+					WarningKind::DuplicateFaceIndex,
+					line.error_at(
 						ErrorKind::InvalidFace,
-						line_index,
+						word,
 						format!(
-							"Failed to parse vertex index as number ({}/{}; \
-							 {})",
-							i, vertex_index_count, err
+							"Vertex index is used more than once ({})",
+							vertex_index
 						),
-					)
-				})?;
-
-			if vertex_index >= vertex_count {
-				return Err(Error::with_message(
-					ErrorKind::InvalidFace,
-					line_index,
-					format!(
-						"Vertex index out of bounds ({}/{})",
-						vertex_index, vertex_count
 					),
 				));
 			}
@@ -407,39 +1252,210 @@ where
 
 		// Check for color
 		let mut words = words.peekable();
+		let first = words.peek().copied();
 
-		let color = if words.peek().is_some() {
-			if let Some(res) = C::try_parse(&mut words) {
-				Some(res.map_err(|err| {
-					Error::with_message(
-						ErrorKind::InvalidColor,
-						line_index,
-						err.to_string(),
-					)
-				})?)
-			} else {
-				None
-			}
+		let color = if !C::HAS_ELEMENTS {
+			// Formats without elements skip the color
+			words.by_ref().for_each(drop);
+			None
+		} else if first.is_some() {
+			C::try_parse_face(&mut words)
+				.transpose()
+				.map_err(|err| color_error(&line, first, err.to_string()))?
 		} else {
 			None
 		};
 
-		if words.next().is_some() {
-			Err(Error::with_message(
-				ErrorKind::Invalid,
-				line_index,
-				"Found elements after color definition",
-			))
+		if let Some(word) = words.next() {
+			Err(line
+				.error_from(
+					ErrorKind::Invalid,
+					word,
+					"Found elements after color definition",
+				)
+				.with_hint(format!(
+					"face declares {} vertices, which may be followed by a \
+					 color",
+					vertex_index_count
+				)))
 		} else {
-			Ok(FaceRef { vertex_refs: vertex_indexes, color })
+			for warning in warnings {
+				self.warn(warning)?;
+			}
+
+			Ok(FaceRef::new(vertex_indexes, color))
+		}
+	}
+}
+
+const LIMIT_HINT: &str =
+	"limits can be raised with `ParserOptions::limits` (see `Limits`)";
+
+/// Describes the elements of each vertex line given by `header`.
+impl<'a, C, L> HeaderSource for OffParser<'a, C, L>
+where
+	C: ColorFormat,
+	L: LineSource<'a>,
+{
+	fn cached_header(&mut self) -> &mut Option<OffHeader> {
+		&mut self.header
+	}
+
+	fn limits(&self) -> &Limits {
+		&self.options.limits
+	}
+
+	fn try_consume_magic(&mut self) -> Result<OffHeader> {
+		let line = self
+			.peek_line()?
+			.ok_or_else(|| Error::without_message(ErrorKind::Empty, 0))?;
+
+		match OffHeader::try_from_magic(&line.text) {
+			Some(Ok(header)) if header.binary => Err(line.error(
+				ErrorKind::InvalidMagic,
+				"Binary data must be parsed with `BinaryOffParser`",
+			)),
+			Some(Ok(header)) => {
+				// valid magic
+				// consume peeked item
+				let _ = self.next_line()?;
+				Ok(header)
+			}
+			Some(Err(msg)) => Err(line.error(ErrorKind::InvalidMagic, msg)),
+			// A missing magic is tolerated, the line is then parsed as counts
+			None => {
+				let warning = Warning::new(
+					WarningKind::MissingMagic,
+					line.error(ErrorKind::InvalidMagic, "Missing `OFF` magic")
+						.with_hint("documents should start with `OFF`"),
+				);
+				self.warn(warning)?;
+
+				Ok(OffHeader::default())
+			}
+		}
+	}
+
+	fn try_consume_dimension(
+		&mut self,
+		check: impl FnOnce(usize) -> Result<(), (ErrorKind, String)>,
+	) -> Result<usize> {
+		let line = self.next_line()?.ok_or_else(|| {
+			self.missing("No dimension for vertices present")
+		})?;
+
+		let dimension = line.text.parse::<usize>().map_err(|err| {
+			line.error(
+				ErrorKind::InvalidDimension,
+				format!("Failed to parse dimension as number ({})", err),
+			)
+		})?;
+
+		check(dimension).map_err(|(kind, msg)| line.error(kind, msg))?;
+
+		Ok(dimension)
+	}
+}
+
+/// Words of a vertex line.
+struct VertexWords<'l, 's, C> {
+	line: &'l SourceLine<'s>,
+	words: Peekable<SplitWhitespace<'l>>,
+	color_format: PhantomData<C>,
+}
+
+impl<'l, 's, C> VertexWords<'l, 's, C> {
+	fn new(line: &'l SourceLine<'s>) -> Self {
+		Self {
+			line,
+			words: line.text.split_whitespace().peekable(),
+			color_format: PhantomData,
+		}
+	}
+}
+
+impl<C: ColorFormat> VertexElements for VertexWords<'_, '_, C> {
+	fn try_consume_floats(
+		&mut self,
+		name: &'static str,
+		values: &mut [f32],
+	) -> Result<()> {
+		try_parse_floats_into(&mut self.words, self.line, name, values)
+	}
+
+	fn try_consume_color(
+		&mut self,
+		header: &OffHeader,
+	) -> Result<Option<Color>> {
+		if !header.vertex_colors {
+			return Ok(None);
+		}
+
+		if !C::HAS_ELEMENTS {
+			// All elements up to the texture coordinates belong to the color
+			let texture_elements = if header.texture_coords { 2 } else { 0 };
+			let color_elements =
+				self.words.clone().count().saturating_sub(texture_elements);
+			self.words.by_ref().take(color_elements).for_each(drop);
+
+			return Ok(None);
+		}
+
+		let first = self.words.peek().copied();
+
+		C::try_parse(&mut self.words)
+			.transpose()
+			.map_err(|err| color_error(self.line, first, err.to_string()))
+	}
+
+	fn error(&self, message: &'static str) -> Error {
+		self.line.error(ErrorKind::InvalidVertex, message)
+	}
+}
+
+fn vertex_layout_hint(header: &OffHeader) -> String {
+	let mut elements =
+		vec![format!("{} coordinates", header.coordinate_count())];
+
+	if header.vertex_normals {
+		elements.push("a normal (3)".into());
+	}
+
+	if header.vertex_colors {
+		elements.push("a color".into());
+	}
+
+	if header.texture_coords {
+		elements.push("texture coordinates (2)".into());
+	}
+
+	format!("`{}` vertices consist of {}", header, elements.join(", "))
+}
+
+/// Error for a color which starts at `first` (or the end of the line if
+/// there are no elements).
+fn color_error(
+	line: &SourceLine<'_>,
+	first: Option<&str>,
+	message: String,
+) -> Error {
+	match first {
+		Some(first) => {
+			line.error_from(ErrorKind::InvalidColor, first, message)
 		}
+		None => line.error_at_end(ErrorKind::InvalidColor, message),
 	}
 }
 
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use crate::colorformat::{RgbU8, RgbaF32};
+	use crate::color::FaceColor;
+	use crate::colorformat::{Indexed, RgbU8, RgbaF32};
+	use crate::colormap::ColorMap;
+	use crate::parse::binary::BinaryOffParser;
+	use crate::write::binary::BinaryOffWriter;
+	use crate::write::{OffWriter, WriterOptions};
 
 	#[test]
 	fn wiki() {
@@ -463,8 +1479,7 @@ mod tests {
 4  3 2 6 7  0 0 255
 4  6 5 4 7  255 0 0"#;
 
-		let options =
-			ParserOptions { color_format: RgbU8, limits: Default::default() };
+		let options = ParserOptions::with_color_format(RgbU8);
 		let parser = OffParser::new_with_options(&content, options);
 		let document = parser.try_parse().unwrap();
 
@@ -497,10 +1512,7 @@ OFF
   4  6 5 4 7  0.000 1.000 0.000 0.75
 "#;
 
-		let options = ParserOptions {
-			color_format: RgbaF32,
-			limits: Default::default(),
-		};
+		let options = ParserOptions::with_color_format(RgbaF32);
 		let parser = OffParser::new_with_options(&content, options);
 		let document = parser.try_parse().unwrap();
 
@@ -513,17 +1525,464 @@ OFF
 		}
 	}
 
+	#[test]
+	fn vertex_colors() {
+		let content = r#"COFF
+4 1 6
+0 0 0  1.0 0.0 0.0 1.0
+1 0 0  0.0 1.0 0.0 1.0
+0 1 0  0.0 0.0 1.0 1.0
+0 0 1  1.0 1.0 1.0 0.5
+3  0 1 2
+"#;
+
+		let options = ParserOptions::with_color_format(RgbaF32);
+		let parser = OffParser::new_with_options(&content, options);
+		let document = parser.try_parse().unwrap().validate().unwrap();
+
+		assert!(document.vertex_normals().is_none());
+
+		let vertex_colors = document.vertex_colors().unwrap();
+		assert_eq!(vertex_colors.len(), 4);
+		assert_eq!(vertex_colors[3], Color::new(255, 255, 255, 127));
+
+		let face = document.face_iter().next().unwrap();
+		assert_eq!(
+			face.vertex_colors.unwrap(),
+			vec![&vertex_colors[0], &vertex_colors[1], &vertex_colors[2]]
+		);
+
+		let err =
+			OffParser::new(&"OFF\n1 0\n0 0 0 1 0 0").try_parse().unwrap_err();
+		assert_eq!(err.kind, ErrorKind::InvalidVertex);
+	}
+
+	#[test]
+	fn vertex_normals() {
+		let content = r#"NOFF
+3 1
+0 0 0  0 0 1
+1 0 0  0 0 1
+0 1 0  0 0.5 0.5
+3  0 1 2
+"#;
+
+		let document = OffParser::new(&content).try_parse().unwrap();
+		let document = document.validate().unwrap();
+
+		assert!(document.vertex_colors().is_none());
+
+		let normals = document.vertex_normals().unwrap();
+		assert_eq!(normals[2], Normal::new(0.0, 0.5, 0.5));
+
+		let face = document.face_iter().next().unwrap();
+		assert_eq!(face.vertex_normals.unwrap()[2], &normals[2]);
+
+		let err =
+			OffParser::new(&"NOFF\n1 0\n0 0 0 1 0").try_parse().unwrap_err();
+		assert_eq!(err.kind, ErrorKind::InvalidVertex);
+	}
+
+	#[test]
+	fn texture_coords() {
+		let content = r#"STCOFF
+3 1
+0 0 0  255 0 0 255  0.0 0.0
+1 0 0  0 255 0 255  1.0 0.0
+0 1 0  0 0 255 255  0.0 1.0
+3  0 1 2
+"#;
+
+		let document = OffParser::new(&content).try_parse().unwrap();
+		let document = document.validate().unwrap();
+
+		assert_eq!(document.vertex_colors().unwrap().len(), 3);
+
+		let texture_coords = document.texture_coords().unwrap();
+		assert_eq!(texture_coords[1], TextureCoord::new(1.0, 0.0));
+
+		let face = document.face_iter().next().unwrap();
+		assert_eq!(face.texture_coords.unwrap()[2], &texture_coords[2]);
+
+		let err =
+			OffParser::new(&"STOFF\n1 0\n0 0 0 1").try_parse().unwrap_err();
+		assert_eq!(err.kind, ErrorKind::InvalidVertex);
+	}
+
+	#[test]
+	fn homogeneous() {
+		let content = "4OFF\n2 0\n2 4 6 2\n1 1 1 0\n";
+
+		let document = OffParser::new(&content).try_parse().unwrap();
+		assert_eq!(document.vertices()[0], Vertex::new(2.0, 4.0, 6.0));
+		assert_eq!(document.vertex_w().unwrap(), [2.0, 0.0]);
+
+		let options = ParserOptions {
+			homogeneous: Homogeneous::Project,
+			..Default::default()
+		};
+		let err = OffParser::new_with_options(&content, options)
+			.try_parse()
+			.unwrap_err();
+		assert_eq!(err.kind, ErrorKind::InvalidVertex);
+		assert_eq!(err.line_index, 3);
+
+		let content = "4OFF\n1 0\n2 4 6 2\n";
+		let document = OffParser::new_with_options(&content, options)
+			.try_parse()
+			.unwrap();
+		assert_eq!(document.vertices()[0], Vertex::new(1.0, 2.0, 3.0));
+		assert!(document.vertex_w().is_none());
+	}
+
+	#[test]
+	fn n_dimensional() {
+		let content = "nOFF\n5\n2 0\n1 2 3 4 5\n6 7 8 9 10\n";
+
+		let document = OffParser::new(&content).try_parse().unwrap();
+		let document = document.validate().unwrap();
+
+		assert_eq!(document.dimension(), 5);
+		assert_eq!(document.vertices()[1], Vertex::new(6.0, 7.0, 8.0));
+
+		let coordinates = document.coordinates().unwrap();
+		assert_eq!(coordinates.len(), 2);
+		assert_eq!(coordinates.get(0).unwrap(), [1.0, 2.0, 3.0, 4.0, 5.0]);
+
+		let content = "4nOFF\n2\n1 0\n2 4 2\n";
+		let document = OffParser::new(&content).try_parse().unwrap();
+		assert_eq!(document.vertices()[0], Vertex::new(2.0, 4.0, 0.0));
+		assert_eq!(document.vertex_w().unwrap(), [2.0]);
+		assert_eq!(
+			document.coordinates().unwrap().get(0).unwrap(),
+			[2.0, 4.0]
+		);
+
+		let mut options = ParserOptions::default();
+		options.limits.dimension = 4;
+		let err = OffParser::new_with_options(&"nOFF\n5\n0 0\n", options)
+			.try_parse()
+			.unwrap_err();
+		assert_eq!(err.kind, ErrorKind::LimitExceeded);
+		assert_eq!(err.line_index, 1);
+
+		let err = OffParser::new(&"nOFF\n0\n0 0\n").try_parse().unwrap_err();
+		assert_eq!(err.kind, ErrorKind::InvalidDimension);
+	}
+
+	#[test]
+	fn color_format_none() {
+		let content = "STCOFF\n3 1\n0 0 0 255 0 0 0 0\n1 0 0 0 1 0 0.5 1 		               0\n0 1 0 0 0 255 255 0 1\n3 0 1 2 0 0 255\n";
+		let options =
+			ParserOptions::with_color_format(crate::colorformat::None);
+
+		let document = OffParser::new_with_options(&content, options)
+			.try_parse()
+			.unwrap();
+
+		assert!(document.header().vertex_colors);
+		assert!(document.vertex_colors().is_none());
+		assert_eq!(document.face_refs()[0].face_color(), None);
+		assert_eq!(
+			document.texture_coords().unwrap()[1],
+			TextureCoord::new(1.0, 0.0)
+		);
+		assert_eq!(document.vertices()[2], Vertex::new(0.0, 1.0, 0.0));
+	}
+
+	#[test]
+	fn colormap_index() {
+		let content =
+			"OFF\n3 2 0\n0 0 0\n1 0 0\n0 1 0\n3 0 1 2 2\n3 0 1 2 0 0 255\n";
+		let options = ParserOptions::with_color_format(Indexed(RgbU8));
+
+		let document = OffParser::new_with_options(&content, options)
+			.try_parse()
+			.unwrap();
+		let document = document.validate().unwrap();
+
+		let colormap = ColorMap::try_parse("0 0 0\n1 1 1\n1 0 0\n").unwrap();
+		let colors = document
+			.face_iter()
+			.map(|face| face.resolve_color(&colormap))
+			.collect::<Vec<_>>();
+		assert_eq!(
+			colors,
+			[
+				Some(Color::new(255, 0, 0, 255)),
+				Some(Color::new(0, 0, 255, 255))
+			]
+		);
+		assert_eq!(
+			document.face_refs()[0].face_color(),
+			Some(FaceColor::Index(2))
+		);
+
+		let options = WriterOptions { color_format: RgbU8, precision: None };
+		let text =
+			OffWriter::new_with_options(&document, options).write_string();
+		assert!(text.ends_with("3 0 1 2 2\n3 0 1 2 0 0 255\n"), "{}", text);
+
+		let bytes = BinaryOffWriter::new(&document).write_bytes();
+		let from_binary = BinaryOffParser::new(&bytes).try_parse().unwrap();
+		assert_eq!(from_binary.face_refs(), document.face_refs());
+
+		let err = OffParser::new(&content).try_parse().unwrap_err();
+		assert_eq!(err.kind, ErrorKind::InvalidColor);
+	}
+
+	#[test]
+	fn recovering() {
+		let content = "COFF\n4 3\n0 0 0 255 0 0\n1 0 0 0 255\n0 1 0 0 0 \
+		               255\n1 1 0 9 9 9\n3 0 1 2\n3 0 1 x\n3 1 2 3 0 0 \
+		               255\nextra\n";
+
+		let (document, errors) = OffParser::new_with_options(
+			&content,
+			ParserOptions::with_color_format(RgbU8),
+		)
+		.try_parse_recovering()
+		.unwrap();
+
+		let problems = errors
+			.iter()
+			.map(|err| (err.kind, err.line_index))
+			.collect::<Vec<_>>();
+		assert_eq!(
+			problems,
+			[
+				(ErrorKind::InvalidColor, 3),
+				(ErrorKind::InvalidFace, 7),
+				(ErrorKind::Invalid, 9),
+			]
+		);
+
+		let document = document.validate().unwrap();
+		assert_eq!(document.vertices().len(), 4);
+		assert_eq!(document.vertices()[1], Vertex::new(0.0, 0.0, 0.0));
+		assert_eq!(document.vertices()[3], Vertex::new(1.0, 1.0, 0.0));
+		assert_eq!(document.vertex_colors().unwrap().len(), 4);
+		assert_eq!(document.face_refs().len(), 2);
+
+		let (document, errors) = OffParser::new(&"OFF\n3 0\n0 0 0\n")
+			.try_parse_recovering()
+			.unwrap();
+		assert_eq!(document.vertices().len(), 3);
+		assert_eq!(errors.len(), 1);
+		assert_eq!(errors[0].kind, ErrorKind::Missing);
+
+		let err =
+			OffParser::new(&"OFF\nx 0\n").try_parse_recovering().unwrap_err();
+		assert_eq!(err.kind, ErrorKind::InvalidCounts);
+	}
+
+	#[test]
+	fn warnings() {
+		let content = "3 3 4\n0 0 0\n1 0 0\n0 1 0\n3 0 1 2\n2 0 1\n3 0 1 1\n";
+
+		let (document, warnings) =
+			OffParser::new(&content).try_parse_with_warnings().unwrap();
+		assert_eq!(document.face_refs().len(), 3);
+
+		let warnings = warnings
+			.iter()
+			.map(|warning| (warning.kind(), warning.line_index()))
+			.collect::<Vec<_>>();
+		assert_eq!(
+			warnings,
+			[
+				(WarningKind::MissingMagic, 0),
+				(WarningKind::DegenerateFace, 5),
+				(WarningKind::DuplicateFaceIndex, 6),
+				(WarningKind::EdgeCountMismatch, 0),
+			]
+		);
+
+		let options = ParserOptions {
+			denied_warnings: DeniedWarnings {
+				duplicate_face_index: true,
+				..Default::default()
+			},
+			..Default::default()
+		};
+
+		let err = OffParser::new_with_options(&content, options)
+			.try_parse()
+			.unwrap_err();
+		assert_eq!(err.kind, ErrorKind::InvalidFace);
+		assert_eq!(err.line_index, 6);
+		assert_eq!(err.span(), Some(content.len() - 2..content.len() - 1));
+
+		let (document, errors) =
+			OffParser::new_with_options(&content, options)
+				.try_parse_recovering()
+				.unwrap();
+		assert_eq!(document.face_refs().len(), 2);
+		assert_eq!(errors.len(), 1);
+
+		let err = OffParser::new_with_options(
+			&content,
+			ParserOptions {
+				denied_warnings: DeniedWarnings::all(),
+				..options
+			},
+		)
+		.try_parse()
+		.unwrap_err();
+		assert_eq!(err.kind, ErrorKind::InvalidMagic);
+
+		// `0` edges are unknown
+		let (_, warnings) =
+			OffParser::new(&"OFF\n3 1 0\n0 0 0\n1 0 0\n0 1 0\n3 0 1 2\n")
+				.try_parse_with_warnings()
+				.unwrap();
+		assert!(warnings.is_empty());
+
+		// The edge count is only checked if requested or denied
+		let content = "OFF\n3 1 2\n0 0 0\n1 0 0\n0 1 0\n3 0 1 2\n";
+		assert!(OffParser::new(&content)
+			.into_events()
+			.all(|event| !matches!(event, Ok(Event::Warning(_)))));
+		assert!(OffParser::new(&content).try_parse().is_ok());
+
+		let err = OffParser::new_with_options(
+			&content,
+			ParserOptions {
+				denied_warnings: DeniedWarnings {
+					edge_count_mismatch: true,
+					..Default::default()
+				},
+				..options
+			},
+		)
+		.try_parse()
+		.unwrap_err();
+		assert_eq!(err.kind, ErrorKind::InvalidCounts);
+		assert_eq!(err.line_index, 1);
+	}
+
+	#[test]
+	fn error_locations() {
+		let content = "OFF\n3 1\n0 0 0\n  1 0 0 # comment\n0 1 0\n3 0 1  7\n";
+
+		let err = OffParser::new(&content).try_parse().unwrap_err();
+		assert_eq!(err.kind(), ErrorKind::InvalidFace);
+		assert_eq!(err.line_index(), 5);
+		assert_eq!(err.column(), Some(7));
+		assert_eq!(&content[err.span().unwrap()], "7");
+		assert!(err.to_string().starts_with("InvalidFace @ ln:6:8 - "));
+
+		let err =
+			OffParser::new(&"OFF\n1 0\n  0 0 x\n").try_parse().unwrap_err();
+		assert_eq!(err.span(), Some(14..15));
+		assert_eq!(err.column(), Some(6));
+
+		// Columns count characters, spans bytes
+		let content = "OFF\n3 1\n0 0 0\n1 0 0\n0 1 0\n\u{a0}3 0 1 7\n";
+		let err = OffParser::new(&content).try_parse().unwrap_err();
+		assert_eq!(err.column(), Some(7));
+		assert_eq!(&content[err.span().unwrap()], "7");
+		assert!(err.to_string().starts_with("InvalidFace @ ln:6:8 - "));
+
+		let err = OffParser::new(&"OFF\n1 0\n0 0\n").try_parse().unwrap_err();
+		assert_eq!(err.kind(), ErrorKind::InvalidVertex);
+		assert_eq!(err.span(), Some(11..11));
+
+		let content = "COFF\n1 0\n0 0 0 1 x 1\n";
+		let err = OffParser::new(&content).try_parse().unwrap_err();
+		assert_eq!(err.kind(), ErrorKind::InvalidColor);
+		assert_eq!(&content[err.span().unwrap()], "1 x 1");
+
+		let err = OffParser::new(&"OFF\n2 0\n0 0 0\n# end\n")
+			.try_parse()
+			.unwrap_err();
+		assert_eq!(err.kind(), ErrorKind::Missing);
+		assert_eq!(err.line_index(), 3);
+		assert_eq!(err.span(), None);
+
+		let mut options = ParserOptions::default();
+		options.limits.face_count = 2;
+		let content = "OFF\n\n0 3\n";
+		let err = OffParser::new_with_options(&content, options)
+			.try_parse()
+			.unwrap_err();
+		assert_eq!(err.kind(), ErrorKind::LimitExceeded);
+		assert_eq!(err.line_index(), 2);
+		assert_eq!(&content[err.span().unwrap()], "3");
+	}
+
+	#[test]
+	fn header() {
+		let mut parser =
+			OffParser::new(&"# comment\nC4nOFF\n2\n1 0\n1 2 1 0 0 1\n");
+		let header = parser.try_parse_header().unwrap();
+
+		assert_eq!(
+			header,
+			OffHeader {
+				vertex_colors: true,
+				homogeneous: true,
+				n_dimensional: true,
+				dimension: 2,
+				..Default::default()
+			}
+		);
+		assert_eq!(parser.try_parse_header().unwrap(), header);
+
+		let document = parser.try_parse().unwrap();
+		assert_eq!(*document.header(), header);
+
+		let err = OffParser::new(&"CXOFF\n0 0\n").try_parse().unwrap_err();
+		assert_eq!(err.kind, ErrorKind::InvalidMagic);
+
+		let err = OffParser::new(&"OFF BINARY\n").try_parse().unwrap_err();
+		assert_eq!(err.kind, ErrorKind::InvalidMagic);
+	}
+
+	#[test]
+	fn from_reader() {
+		for res in std::fs::read_dir("resources").unwrap() {
+			let res = res.expect("Failed to get resources");
+			let content = std::fs::read_to_string(res.path()).unwrap();
+			let file = std::fs::File::open(res.path()).unwrap();
+
+			let from_str = OffParser::new(&content).try_parse();
+			let from_reader =
+				OffParser::from_reader(std::io::BufReader::new(file))
+					.try_parse();
+
+			assert_eq!(from_str, from_reader, "{}", res.path().display());
+		}
+
+		let content = "OFF # magic\r\n\r\n# counts\n1 0\n\n0 0\n";
+		let err = OffParser::from_reader(content.as_bytes())
+			.try_parse()
+			.unwrap_err();
+		assert_eq!(err, OffParser::new(&content).try_parse().unwrap_err());
+		assert_eq!(err.line_index, 5);
+
+		let err = OffParser::from_reader(&b"OFF\n1 0\n\xff 0 0\n"[..])
+			.try_parse()
+			.unwrap_err();
+		assert_eq!(err.kind, ErrorKind::Io);
+		assert_eq!(err.line_index, 2);
+	}
+
 	#[test]
 	fn parse_resources() {
 		for res in std::fs::read_dir("resources").unwrap() {
 			let res = res.expect("Failed to get resources");
-			let content = std::fs::read_to_string(res.path())
-				.expect(&format!("Failed to read: {}", res.path().display()));
+			let content =
+				std::fs::read_to_string(res.path()).unwrap_or_else(|_| {
+					panic!("Failed to read: {}", res.path().display())
+				});
 
 			let parser = OffParser::new(&content);
-			let _ = parser
-				.try_parse()
-				.expect(&format!("Failed to parse: {}", res.path().display()));
+			let (_, warnings) =
+				parser.try_parse_with_warnings().unwrap_or_else(|_| {
+					panic!("Failed to parse: {}", res.path().display())
+				});
+			assert_eq!(warnings, [], "{}", res.path().display());
 		}
 	}
 }
@@ -0,0 +1,384 @@
+use std::ops::Range;
+
+use super::{
+	parse_header, parse_vertex, Error, ErrorKind, HeaderSource, Limits,
+	ParserOptions, Result, VertexElements, Vertices,
+};
+use crate::color::{Color, FaceColor};
+use crate::document::{Document, Unchecked};
+use crate::face::FaceRef;
+use crate::header::OffHeader;
+use crate::vertex::VertexRef;
+
+/// Parser for the binary variant of the OFF format (`OFF BINARY`).
+///
+/// After the header line all values are stored as big-endian 32-bit
+/// integers (counts and indices) or floats (coordinates and colors). Faces
+/// store the number of their color elements before the elements themselves.
+///
+/// The color format of the options is not used, as binary colors are always
+/// stored as floats.
+pub struct BinaryOffParser<'a, C> {
+	options: ParserOptions<C>,
+	bytes: &'a [u8],
+	offset: usize,
+	/// Bytes of the last consumed (or missing) word
+	word: Range<usize>,
+	line_index: usize,
+	header: Option<OffHeader>,
+}
+
+impl<'a> BinaryOffParser<'a, crate::colorformat::Any> {
+	pub fn new(bytes: &'a [u8]) -> Self {
+		Self::new_with_options(bytes, Default::default())
+	}
+}
+
+impl<'a, C> BinaryOffParser<'a, C> {
+	pub fn new_with_options(
+		bytes: &'a [u8],
+		options: ParserOptions<C>,
+	) -> Self {
+		Self {
+			bytes,
+			offset: 0,
+			word: 0..0,
+			line_index: 0,
+			options,
+			header: None,
+		}
+	}
+
+	/// Parses and caches the header, see
+	/// [`OffParser::try_parse_header`](super::OffParser::try_parse_header).
+	pub fn try_parse_header(&mut self) -> Result<OffHeader> {
+		parse_header(self)
+	}
+
+	pub fn try_parse(mut self) -> Result<Document<Unchecked>> {
+		let header = self.try_parse_header()?;
+		let (vertex_count, face_count, edge_count) =
+			self.try_consume_counts()?;
+
+		let mut vertices = Vertices::with_capacity(&header, vertex_count);
+		let homogeneous = self.options.homogeneous;
+
+		for _ in 0..vertex_count {
+			let (vertex, attributes) =
+				parse_vertex(&mut self, &header, homogeneous)?;
+			vertices.push(vertex, attributes);
+		}
+
+		let faces = (0..face_count)
+			.map(|_| self.try_consume_face(vertex_count))
+			.collect::<Result<_>>()?;
+
+		if self.offset < self.bytes.len() {
+			Err(self.error(
+				ErrorKind::Invalid,
+				"Unexpected data after OFF definition",
+			))
+		} else {
+			Ok(vertices.into_document(faces, Some(edge_count)))
+		}
+	}
+
+	fn try_consume_counts(&mut self) -> Result<(usize, usize, u64)> {
+		let vertex_count = self.try_consume_count(ErrorKind::InvalidCounts)?;
+
+		if vertex_count > self.options.limits.vertex_count {
+			return Err(self.error(
+				ErrorKind::LimitExceeded,
+				format!(
+					"Vertex count exceeds limit (limit: {}, requested: {})",
+					self.options.limits.vertex_count, vertex_count
+				),
+			));
+		}
+
+		let face_count = self.try_consume_count(ErrorKind::InvalidCounts)?;
+
+		if face_count > self.options.limits.face_count {
+			return Err(self.error(
+				ErrorKind::LimitExceeded,
+				format!(
+					"Face count exceeds limit (limit: {}, requested: {})",
+					self.options.limits.face_count, face_count
+				),
+			));
+		}
+
+		let edge_count = self.try_consume_count(ErrorKind::InvalidCounts)?;
+
+		Ok((vertex_count, face_count, edge_count as u64))
+	}
+
+	fn try_consume_face(&mut self, vertex_count: usize) -> Result<FaceRef> {
+		let vertex_index_count =
+			self.try_consume_count(ErrorKind::InvalidFace)?;
+
+		if vertex_index_count > self.options.limits.face_vertex_count {
+			return Err(self.error(
+				ErrorKind::LimitExceeded,
+				format!(
+					"Vertex count of face exceeds limit (limit: {}, \
+					 requested: {})",
+					self.options.limits.face_vertex_count, vertex_index_count
+				),
+			));
+		}
+
+		let mut vertex_indexes = Vec::with_capacity(vertex_index_count);
+
+		for _ in 0..vertex_index_count {
+			let vertex_index =
+				self.try_consume_count(ErrorKind::InvalidFace)?;
+
+			if vertex_index >= vertex_count {
+				return Err(self.error(
+					ErrorKind::InvalidFace,
+					format!(
+						"Vertex index out of bounds ({}/{})",
+						vertex_index, vertex_count
+					),
+				));
+			}
+
+			vertex_indexes.push(VertexRef(vertex_index));
+		}
+
+		let color = self.try_consume_color()?;
+
+		Ok(FaceRef::new(vertex_indexes, color))
+	}
+
+	fn try_consume_color(&mut self) -> Result<Option<FaceColor>> {
+		let element_count = self.try_consume_count(ErrorKind::InvalidColor)?;
+
+		let color = match element_count {
+			0 => return Ok(None),
+			1 => {
+				let index = self.try_consume_count(ErrorKind::InvalidColor)?;
+				return Ok(Some(FaceColor::Index(index)));
+			}
+			3 | 4 => {
+				let red = self.try_consume_f32(ErrorKind::InvalidColor)?;
+				let green = self.try_consume_f32(ErrorKind::InvalidColor)?;
+				let blue = self.try_consume_f32(ErrorKind::InvalidColor)?;
+				let alpha = if element_count == 4 {
+					self.try_consume_f32(ErrorKind::InvalidColor)?
+				} else {
+					1.0
+				};
+
+				Color::try_from_f32(red, green, blue, alpha)
+			}
+			_ => {
+				return Err(self.error(
+					ErrorKind::InvalidColor,
+					format!(
+						"Unsupported number of color elements (expected: \
+						 0/1/3/4, actual: {})",
+						element_count
+					),
+				))
+			}
+		};
+
+		color.map(|color| Some(FaceColor::Color(color))).map_err(|err| {
+			self.error(ErrorKind::InvalidColor, err.to_string())
+		})
+	}
+
+	fn try_consume_rgba(&mut self) -> Result<Color> {
+		let red = self.try_consume_f32(ErrorKind::InvalidColor)?;
+		let green = self.try_consume_f32(ErrorKind::InvalidColor)?;
+		let blue = self.try_consume_f32(ErrorKind::InvalidColor)?;
+		let alpha = self.try_consume_f32(ErrorKind::InvalidColor)?;
+
+		Color::try_from_f32(red, green, blue, alpha).map_err(|err| {
+			self.error(ErrorKind::InvalidColor, err.to_string())
+		})
+	}
+
+	fn try_consume_count(&mut self, kind: ErrorKind) -> Result<usize> {
+		let value = i32::from_be_bytes(self.try_consume_word(kind)?);
+
+		usize::try_from(value).map_err(|_| {
+			self.error(
+				kind,
+				format!("Expected positive number (got: {})", value),
+			)
+		})
+	}
+
+	fn try_consume_f32(&mut self, kind: ErrorKind) -> Result<f32> {
+		Ok(f32::from_be_bytes(self.try_consume_word(kind)?))
+	}
+
+	fn try_consume_word(&mut self, kind: ErrorKind) -> Result<[u8; 4]> {
+		self.word = self.offset..(self.offset + 4).min(self.bytes.len());
+
+		let word = self
+			.bytes
+			.get(self.offset..self.offset + 4)
+			.and_then(|word| word.try_into().ok())
+			.ok_or_else(|| {
+				self.error(kind, "Unexpected end of binary data")
+			})?;
+
+		self.offset += 4;
+
+		Ok(word)
+	}
+
+	/// Creates an error for the last consumed word of the binary section. As
+	/// it contains no lines the index of the header line is used and the
+	/// byte offset is added to the message.
+	fn error<M: Into<String>>(&self, kind: ErrorKind, message: M) -> Error {
+		Error::with_message(
+			kind,
+			self.line_index,
+			format!("{} (byte: {})", message.into(), self.word.start),
+		)
+		.with_span(self.word.clone(), None)
+	}
+}
+
+impl<C> HeaderSource for BinaryOffParser<'_, C> {
+	fn cached_header(&mut self) -> &mut Option<OffHeader> {
+		&mut self.header
+	}
+
+	fn limits(&self) -> &Limits {
+		&self.options.limits
+	}
+
+	fn try_consume_magic(&mut self) -> Result<OffHeader> {
+		// The header is the first line which is neither empty nor a comment.
+		while self.offset < self.bytes.len() {
+			let rest = &self.bytes[self.offset..];
+			let line_len =
+				rest.iter().position(|&b| b == b'\n').unwrap_or(rest.len());
+			let span = self.offset..self.offset + line_len;
+			let line =
+				std::str::from_utf8(&rest[..line_len]).map_err(|_| {
+					Error::with_message(
+						ErrorKind::InvalidMagic,
+						self.line_index,
+						"Header is not valid utf-8",
+					)
+					.with_span(span.clone(), Some(0))
+				})?;
+
+			self.offset += (line_len + 1).min(rest.len());
+
+			let line = line.split('#').next().unwrap_or_default().trim();
+
+			if line.is_empty() {
+				self.line_index += 1;
+				continue;
+			}
+
+			return match OffHeader::try_from_magic(line) {
+				Some(Ok(header)) if header.binary => Ok(header),
+				Some(Err(msg)) => Err(Error::with_message(
+					ErrorKind::InvalidMagic,
+					self.line_index,
+					msg,
+				)
+				.with_span(span, Some(0))),
+				_ => Err(Error::with_message(
+					ErrorKind::InvalidMagic,
+					self.line_index,
+					"Expected binary magic (e.g. `OFF BINARY`)",
+				)
+				.with_span(span, Some(0))),
+			};
+		}
+
+		Err(Error::without_message(ErrorKind::Empty, 0))
+	}
+
+	fn try_consume_dimension(
+		&mut self,
+		check: impl FnOnce(usize) -> Result<(), (ErrorKind, String)>,
+	) -> Result<usize> {
+		let dimension = self.try_consume_count(ErrorKind::InvalidDimension)?;
+
+		check(dimension).map_err(|(kind, msg)| self.error(kind, msg))?;
+
+		Ok(dimension)
+	}
+}
+
+impl<C> VertexElements for BinaryOffParser<'_, C> {
+	fn try_consume_floats(
+		&mut self,
+		_name: &'static str,
+		values: &mut [f32],
+	) -> Result<()> {
+		for value in values {
+			*value = self.try_consume_f32(ErrorKind::InvalidVertex)?;
+		}
+
+		Ok(())
+	}
+
+	fn try_consume_color(
+		&mut self,
+		header: &OffHeader,
+	) -> Result<Option<Color>> {
+		header.vertex_colors.then(|| self.try_consume_rgba()).transpose()
+	}
+
+	fn error(&self, message: &'static str) -> Error {
+		BinaryOffParser::error(self, ErrorKind::InvalidVertex, message)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::colorformat::RgbaF32;
+	use crate::parse::OffParser;
+	use crate::write::binary::BinaryOffWriter;
+
+	#[test]
+	fn same_as_text() {
+		let content = include_str!("../../resources/prinston.off");
+		let options = ParserOptions::with_color_format(RgbaF32);
+		let text = OffParser::new_with_options(&content, options)
+			.try_parse()
+			.unwrap();
+
+		let bytes = BinaryOffWriter::new(&text).write_bytes();
+		let binary = BinaryOffParser::new(&bytes).try_parse().unwrap();
+
+		assert!(binary.header().binary);
+		assert_eq!(text, binary);
+	}
+
+	#[test]
+	fn limits() {
+		let content = include_str!("../../resources/prinston.off");
+		let text = OffParser::new(&content).try_parse().unwrap();
+		let bytes = BinaryOffWriter::new(&text).write_bytes();
+
+		let mut options = ParserOptions::default();
+		options.limits.face_count = 2;
+
+		let err = BinaryOffParser::new_with_options(&bytes, options)
+			.try_parse()
+			.unwrap_err();
+
+		assert_eq!(err.kind, ErrorKind::LimitExceeded);
+
+		let err = BinaryOffParser::new(&bytes[..bytes.len() - 2])
+			.try_parse()
+			.unwrap_err();
+
+		assert_eq!(err.kind, ErrorKind::InvalidColor);
+		assert_eq!(err.span(), Some(bytes.len() - 4..bytes.len() - 2));
+	}
+}
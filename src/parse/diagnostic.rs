@@ -0,0 +1,172 @@
+use std::fmt;
+
+use super::Error;
+
+/// Renders an [`Error`] for humans: the `ErrorKind` and message, the
+/// offending line of the source with a caret under the bad token and a hint
+/// (if known).
+///
+/// ```text
+/// error[InvalidFace]: Expected vertex index (3/4)
+///  --> ln:6:8
+///   |
+/// 6 | 4 0 1 2
+///   |        ^
+///   = hint: face declares 4 vertices but lists 3
+/// ```
+///
+/// `source` must be the document the error was created for. Errors of
+/// binary documents are located by their byte offset instead of a line.
+#[derive(Debug, Clone, Copy)]
+pub struct Diagnostic<'a> {
+	error: &'a Error,
+	source: &'a str,
+}
+
+impl<'a> Diagnostic<'a> {
+	pub fn new(error: &'a Error, source: &'a str) -> Self {
+		Self { error, source }
+	}
+
+	/// Line of the error and the part before the span.
+	fn snippet(&self) -> Option<(&'a str, &'a str)> {
+		let line = self.source.lines().nth(self.error.line_index)?;
+		let column = self.error.column?;
+		let end = line
+			.char_indices()
+			.map(|(i, _)| i)
+			.chain([line.len()])
+			.nth(column)?;
+		let before = &line[..end];
+
+		Some((line, before))
+	}
+
+	/// Number of characters to underline (at least one).
+	fn caret_width(&self) -> usize {
+		self.error
+			.span
+			.clone()
+			.and_then(|span| self.source.get(span))
+			.map_or(0, |token| {
+				token.lines().next().unwrap_or("").chars().count()
+			})
+			.max(1)
+	}
+}
+
+impl fmt::Display for Diagnostic<'_> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let error = self.error;
+		let line_number = error.line_index + 1;
+		let pad = " ".repeat(line_number.to_string().len());
+
+		write!(f, "error[{}]", error.kind)?;
+
+		if let Some(msg) = &error.message {
+			write!(f, ": {}", msg)?;
+		}
+
+		match (error.column, &error.span) {
+			(Some(column), _) => {
+				write!(f, "\n{}--> ln:{}:{}", pad, line_number, column + 1)?
+			}
+			(None, Some(span)) => {
+				write!(f, "\n{}--> byte:{}", pad, span.start)?
+			}
+			(None, None) => write!(f, "\n{}--> ln:{}", pad, line_number)?,
+		}
+
+		if let Some((line, before)) = self.snippet() {
+			// Keep tabs so that the caret lines up with the token
+			let indent = before
+				.chars()
+				.map(|c| if c == '\t' { '\t' } else { ' ' })
+				.collect::<String>();
+
+			write!(f, "\n{} |", pad)?;
+			write!(f, "\n{} | {}", line_number, line)?;
+			write!(
+				f,
+				"\n{} | {}{}",
+				pad,
+				indent,
+				"^".repeat(self.caret_width())
+			)?;
+		}
+
+		if let Some(hint) = &error.hint {
+			write!(f, "\n{} = hint: {}", pad, hint)?;
+		}
+
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::parse::binary::BinaryOffParser;
+	use crate::parse::OffParser;
+
+	#[test]
+	fn render() {
+		let content =
+			"OFF\n# faces are incomplete\n3 1\n0 0 0\n1 0 0\n0 1 0\n4 0 1 2\n";
+		let err = OffParser::new(&content).try_parse().unwrap_err();
+
+		assert_eq!(
+			err.diagnostic(content).to_string(),
+			"error[InvalidFace]: Expected vertex index (3/4)
+ --> ln:7:8
+  |
+7 | 4 0 1 2
+  |        ^
+  = hint: face declares 4 vertices but lists 3"
+		);
+
+		let content = "OFF\n3 1\n0 0 0\n1 0 0\n0 1 0\n\n\n\n\n\t3 0 1 10\n";
+		let err = OffParser::new(&content).try_parse().unwrap_err();
+
+		assert_eq!(
+			err.diagnostic(content).to_string(),
+			"error[InvalidFace]: Vertex index out of bounds (10/3)
+  --> ln:10:8
+   |
+10 | \t3 0 1 10
+   | \t      ^^
+   = hint: valid vertex indices are 0 to 2"
+		);
+
+		// The no-break space is one character, but two bytes
+		let content = "OFF\n1 0\n\u{a0}0 0 x\n";
+		let err = OffParser::new(&content).try_parse().unwrap_err();
+
+		assert!(err
+			.diagnostic(content)
+			.to_string()
+			.ends_with("\n3 | \u{a0}0 0 x\n  |      ^"));
+	}
+
+	#[test]
+	fn render_without_line() {
+		let content = "OFF\n2 0\n0 0 0\n";
+		let err = OffParser::new(&content).try_parse().unwrap_err();
+
+		assert_eq!(
+			err.diagnostic(content).to_string(),
+			"error[Missing]: Expected vertex
+ --> ln:4
+  = hint: the counts declare 2 vertices but only 1 are given"
+		);
+
+		let err = BinaryOffParser::new(&b"OFF BINARY\n\0\0"[..])
+			.try_parse()
+			.unwrap_err();
+
+		assert_eq!(
+			err.diagnostic("").to_string(),
+			"error[InvalidCounts]: Unexpected end of binary data (byte: 11)
+ --> byte:11"
+		);
+	}
+}
@@ -0,0 +1,346 @@
+use std::collections::HashSet;
+
+use super::warning::{Warning, WarningKind};
+use super::{
+	Error, ErrorKind, LineSource, OffLines, OffParser, Result,
+	VertexAttributes,
+};
+use crate::colorformat::ColorFormat;
+use crate::face::FaceRef;
+use crate::header::OffHeader;
+use crate::vertex::Vertex;
+
+/// Single step of parsing a document (see [`OffParser::into_events`]).
+///
+/// Events are emitted in the order of the document: `Header`, `Counts`, all
+/// `Vertex` and `Face` events and finally `End`. A `Warning` directly follows
+/// the event it belongs to.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+	Header(OffHeader),
+	Counts {
+		vertex_count: usize,
+		face_count: usize,
+		edge_count: Option<u64>,
+	},
+	/// Index and position of a vertex together with all its other
+	/// attributes.
+	Vertex(usize, Vertex, VertexAttributes),
+	Face(usize, FaceRef),
+	/// Non-fatal problem of the document (see [`Warning`]).
+	Warning(Warning),
+	/// The whole document was parsed and no unexpected lines follow.
+	End,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+	Header,
+	Counts(OffHeader),
+	Vertices { header: OffHeader, index: usize, count: usize, faces: usize },
+	Faces { index: usize, count: usize, vertices: usize },
+	End,
+	Done,
+}
+
+/// Iterator over the [`Event`]s of a document.
+///
+/// No event is emitted after the first error, unless the iterator is
+/// [`recovering`](OffEvents::recovering).
+pub struct OffEvents<'a, C, L = OffLines<'a>> {
+	parser: OffParser<'a, C, L>,
+	state: State,
+	recover: bool,
+	check_edges: bool,
+	/// Line index and value of the declared edge count (unless unknown or
+	/// not checked)
+	declared_edges: Option<(usize, u64)>,
+	/// Edges of all faces, only collected if the edge count is checked
+	edges: HashSet<(usize, usize)>,
+}
+
+impl<'a, C, L> OffEvents<'a, C, L>
+where
+	C: ColorFormat,
+	L: LineSource<'a>,
+{
+	pub fn new(parser: OffParser<'a, C, L>) -> Self {
+		Self {
+			parser,
+			state: State::Header,
+			recover: false,
+			check_edges: false,
+			declared_edges: None,
+			edges: HashSet::new(),
+		}
+	}
+
+	/// Continues after errors in vertex and face lines. The malformed line
+	/// is skipped, so that no event is emitted for its vertex or face.
+	///
+	/// Errors in the header or counts, missing lines and failed reads still
+	/// end the iteration.
+	#[must_use]
+	pub fn recovering(mut self) -> Self {
+		self.recover = true;
+		self
+	}
+
+	/// Emits a [`WarningKind::EdgeCountMismatch`] warning if the declared
+	/// edge count does not match the faces. This requires keeping all edges
+	/// in memory, so the check is only done if enabled here or if the
+	/// warning is denied (see [`ParserOptions::denied_warnings`]).
+	///
+	/// [`ParserOptions::denied_warnings`]: super::ParserOptions::denied_warnings
+	#[must_use]
+	pub fn checking_edge_count(mut self) -> Self {
+		self.check_edges = true;
+		self
+	}
+
+	fn try_next(&mut self) -> Result<Option<Event>> {
+		loop {
+			if self.state != State::Done {
+				if let Some(warning) = self.parser.warnings.pop_front() {
+					return Ok(Some(Event::Warning(warning)));
+				}
+			}
+
+			let event = match self.state {
+				State::Header => {
+					let header = self.parser.try_parse_header()?;
+					self.state = State::Counts(header);
+
+					Event::Header(header)
+				}
+				State::Counts(header) => {
+					let (vertex_count, face_count, edge_count) =
+						self.parser.try_consume_counts()?;
+					let check = self.check_edges
+						|| self
+							.parser
+							.options
+							.denied_warnings
+							.edge_count_mismatch;
+					// `0` is commonly written instead of the real count
+					self.declared_edges = edge_count
+						.filter(|&count| check && count != 0)
+						.map(|count| (self.parser.line_index - 1, count));
+					self.state = State::Vertices {
+						header,
+						index: 0,
+						count: vertex_count,
+						faces: face_count,
+					};
+
+					Event::Counts { vertex_count, face_count, edge_count }
+				}
+				State::Vertices { index, count, faces, .. }
+					if index == count =>
+				{
+					self.state = State::Faces {
+						index: 0,
+						count: faces,
+						vertices: count,
+					};
+					continue;
+				}
+				State::Vertices { header, index, count, faces } => {
+					// Advance first so that a malformed line can be skipped
+					self.state = State::Vertices {
+						header,
+						index: index + 1,
+						count,
+						faces,
+					};
+					let (vertex, attributes) =
+						self.parser.try_consume_vertex(&header).map_err(
+							|err| missing_hint(err, "vertices", index, count),
+						)?;
+
+					Event::Vertex(index, vertex, attributes)
+				}
+				State::Faces { index, count, .. } if index == count => {
+					self.state = State::End;
+					self.check_edge_count()?;
+					continue;
+				}
+				State::Faces { index, count, vertices } => {
+					self.state =
+						State::Faces { index: index + 1, count, vertices };
+					let face =
+						self.parser.try_consume_face(vertices).map_err(
+							|err| missing_hint(err, "faces", index, count),
+						)?;
+
+					if self.declared_edges.is_some() {
+						self.add_edges(&face);
+					}
+
+					Event::Face(index, face)
+				}
+				State::End => {
+					self.state = State::Done;
+					self.parser.try_consume_end()?;
+
+					Event::End
+				}
+				State::Done => return Ok(None),
+			};
+
+			return Ok(Some(event));
+		}
+	}
+
+	fn add_edges(&mut self, face: &FaceRef) {
+		self.edges.extend(face.undirected_edges());
+	}
+
+	/// Warns if the declared edge count does not match the faces.
+	fn check_edge_count(&mut self) -> Result<()> {
+		let (line_index, declared) = match self.declared_edges {
+			Some(declared) => declared,
+			None => return Ok(()),
+		};
+		let actual = self.edges.len() as u64;
+
+		if declared == actual {
+			return Ok(());
+		}
+
+		let error = Error::with_message(
+			WarningKind::EdgeCountMismatch.error_kind(),
+			line_index,
+			format!(
+				"Edge count does not match the faces (declared: {}, actual: \
+				 {})",
+				declared, actual
+			),
+		)
+		.with_hint("the edge count can also be given as `0` (unknown)");
+
+		self.parser.warn(Warning::new(WarningKind::EdgeCountMismatch, error))
+	}
+}
+
+/// Adds a hint with the declared count to errors for missing lines.
+fn missing_hint(err: Error, name: &str, index: usize, count: usize) -> Error {
+	if err.kind == ErrorKind::Missing {
+		err.with_hint(format!(
+			"the counts declare {} {} but only {} are given",
+			count, name, index
+		))
+	} else {
+		err
+	}
+}
+
+impl<'a, C, L> Iterator for OffEvents<'a, C, L>
+where
+	C: ColorFormat,
+	L: LineSource<'a>,
+{
+	type Item = Result<Event>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let event = self.try_next();
+
+		if let Err(err) = &event {
+			let recoverable =
+				self.recover
+					&& matches!(
+						self.state,
+						State::Vertices { .. } | State::Faces { .. }
+					) && !matches!(err.kind, ErrorKind::Missing | ErrorKind::Io);
+
+			if !recoverable {
+				self.state = State::Done;
+			}
+		}
+
+		event.transpose()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::color::{Color, FaceColor};
+	use crate::parse::ErrorKind;
+
+	#[test]
+	fn events() {
+		let content = "OFF\n3 1\n0 0 0\n1 0 0\n0 1 0\n3 0 2 1 0 0 255\n";
+
+		let events = OffParser::new(&content)
+			.into_events()
+			.collect::<Result<Vec<_>>>()
+			.unwrap();
+
+		assert_eq!(events.len(), 7);
+		assert_eq!(events[0], Event::Header(OffHeader::default()));
+		assert_eq!(
+			events[1],
+			Event::Counts { vertex_count: 3, face_count: 1, edge_count: None }
+		);
+		assert_eq!(
+			events[3],
+			Event::Vertex(
+				1,
+				Vertex::new(1.0, 0.0, 0.0),
+				VertexAttributes::default()
+			)
+		);
+
+		match &events[5] {
+			Event::Face(0, face) => {
+				let indices = face
+					.vertex_refs()
+					.iter()
+					.map(|vertex_ref| vertex_ref.index())
+					.collect::<Vec<_>>();
+
+				assert_eq!(indices, [0, 2, 1]);
+				assert_eq!(
+					face.face_color(),
+					Some(FaceColor::Color(Color::new(0, 0, 255, 255)))
+				);
+			}
+			event => panic!("Expected face, got {:?}", event),
+		}
+
+		assert_eq!(events[6], Event::End);
+	}
+
+	#[test]
+	fn filter() {
+		let content = "OFF\n3 0\n0 0 0\n1 0 0\n0 1 0\n";
+
+		let xs = OffParser::new(&content)
+			.into_events()
+			.filter_map(|event| match event {
+				Ok(Event::Vertex(_, vertex, _)) => Some(vertex.x),
+				_ => None,
+			})
+			.collect::<Vec<_>>();
+
+		assert_eq!(xs, [0.0, 1.0, 0.0]);
+	}
+
+	#[test]
+	fn stops_after_error() {
+		let content = "OFF\n2 0\n0 0 0\n1 0\n0 1 0\n";
+
+		let mut events = OffParser::new(&content).into_events();
+
+		assert!(matches!(events.next(), Some(Ok(Event::Header(_)))));
+		assert!(matches!(events.next(), Some(Ok(Event::Counts { .. }))));
+		assert!(matches!(events.next(), Some(Ok(Event::Vertex(0, ..)))));
+
+		let err = events.next().unwrap().unwrap_err();
+		assert_eq!(err.kind, ErrorKind::InvalidVertex);
+		assert_eq!(err.line_index, 3);
+
+		assert!(events.next().is_none());
+	}
+}
@@ -0,0 +1,168 @@
+use super::{VertexAttributes, Vertices};
+use crate::color::{Color, FaceColor};
+use crate::document::{Document, Unchecked};
+use crate::face::FaceRef;
+use crate::header::OffHeader;
+use crate::vertex::{Vertex, VertexRef};
+
+/// Receives the parts of a document while it is parsed (see
+/// [`OffParser::try_parse_into`](super::OffParser::try_parse_into)).
+///
+/// The callbacks are called in the order of the document: `header`,
+/// `counts`, `vertex` (followed by `vertex_color`) for each vertex and
+/// `face` (followed by `face_color`) for each face. Colors are only passed
+/// to the color callbacks and never as part of the vertex attributes.
+pub trait MeshSink {
+	type Output;
+
+	fn header(&mut self, _header: &OffHeader) {}
+
+	/// Number of vertices, faces and edges as given by the document. Can be
+	/// used to reserve memory up front.
+	fn counts(
+		&mut self,
+		_vertex_count: usize,
+		_face_count: usize,
+		_edge_count: Option<u64>,
+	) {
+	}
+
+	fn vertex(
+		&mut self,
+		index: usize,
+		vertex: Vertex,
+		attributes: VertexAttributes,
+	);
+
+	fn vertex_color(&mut self, _index: usize, _color: Color) {}
+
+	/// All vertex references are in bounds of the vertex count.
+	fn face(&mut self, index: usize, vertex_refs: Vec<VertexRef>);
+
+	fn face_color(&mut self, _index: usize, _color: FaceColor) {}
+
+	/// Called once after the whole document was parsed successfully.
+	fn finish(self) -> Self::Output;
+}
+
+/// Sink which builds a [`Document`]. This is what
+/// [`OffParser::try_parse`](super::OffParser::try_parse) uses.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct DocumentSink {
+	header: OffHeader,
+	vertices: Vertices,
+	face_refs: Vec<FaceRef>,
+	edge_count: Option<u64>,
+}
+
+impl MeshSink for DocumentSink {
+	type Output = Document<Unchecked>;
+
+	fn header(&mut self, header: &OffHeader) {
+		self.header = *header;
+	}
+
+	fn counts(
+		&mut self,
+		vertex_count: usize,
+		face_count: usize,
+		edge_count: Option<u64>,
+	) {
+		self.vertices = Vertices::with_capacity(&self.header, vertex_count);
+		self.face_refs.reserve_exact(face_count);
+		self.edge_count = edge_count;
+	}
+
+	fn vertex(
+		&mut self,
+		_index: usize,
+		vertex: Vertex,
+		attributes: VertexAttributes,
+	) {
+		self.vertices.push(vertex, attributes);
+	}
+
+	fn vertex_color(&mut self, _index: usize, color: Color) {
+		self.vertices.push_color(color);
+	}
+
+	fn face(&mut self, _index: usize, vertex_refs: Vec<VertexRef>) {
+		self.face_refs.push(FaceRef::new(vertex_refs, None));
+	}
+
+	fn face_color(&mut self, _index: usize, color: FaceColor) {
+		// The color directly follows its face
+		if let Some(face_ref) = self.face_refs.last_mut() {
+			*face_ref = FaceRef::new(
+				std::mem::take(&mut face_ref.vertex_refs),
+				Some(color),
+			);
+		}
+	}
+
+	fn finish(self) -> Self::Output {
+		self.vertices.into_document(self.face_refs, self.edge_count)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::parse::OffParser;
+
+	/// Sink for a mesh type which stores triangles only.
+	#[derive(Default)]
+	struct Triangles {
+		positions: Vec<[f32; 3]>,
+		indices: Vec<[usize; 3]>,
+		colors: usize,
+	}
+
+	impl MeshSink for Triangles {
+		type Output = Self;
+
+		fn counts(
+			&mut self,
+			vertex_count: usize,
+			face_count: usize,
+			_: Option<u64>,
+		) {
+			self.positions.reserve_exact(vertex_count);
+			self.indices.reserve(face_count);
+		}
+
+		fn vertex(&mut self, _: usize, vertex: Vertex, _: VertexAttributes) {
+			self.positions.push([vertex.x, vertex.y, vertex.z]);
+		}
+
+		fn face(&mut self, _: usize, vertex_refs: Vec<VertexRef>) {
+			let first = vertex_refs[0].index();
+
+			for pair in vertex_refs[1..].windows(2) {
+				self.indices.push([first, pair[0].index(), pair[1].index()]);
+			}
+		}
+
+		fn face_color(&mut self, _: usize, _: FaceColor) {
+			self.colors += 1;
+		}
+
+		fn finish(self) -> Self::Output {
+			self
+		}
+	}
+
+	#[test]
+	fn custom_sink() {
+		let content = include_str!("../../resources/prinston.off");
+
+		let triangles = OffParser::new(&content)
+			.try_parse_into(Triangles::default())
+			.unwrap();
+		let document = OffParser::new(&content).try_parse().unwrap();
+
+		assert_eq!(triangles.positions.len(), document.vertices().len());
+		assert_eq!(triangles.indices.len(), document.face_refs().len() * 2);
+		assert_eq!(triangles.colors, document.face_refs().len());
+	}
+}
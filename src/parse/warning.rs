@@ -0,0 +1,140 @@
+use std::fmt;
+use std::ops::Range;
+
+use super::{Error, ErrorKind};
+
+/// Something suspicious about a document which is not an error by itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WarningKind {
+	/// The document does not start with an `OFF` magic. The first line is
+	/// parsed as counts instead.
+	MissingMagic,
+	/// The declared edge count differs from the number of edges of the
+	/// faces. An edge count of `0` is treated as unknown, as it is commonly
+	/// written instead of the real count.
+	///
+	/// Only checked if the warnings are requested or this warning is denied
+	/// (see [`OffEvents::checking_edge_count`]), as all edges have to be
+	/// collected. Otherwise see [`Document::count_edges`].
+	///
+	/// [`OffEvents::checking_edge_count`]: super::event::OffEvents::checking_edge_count
+	/// [`Document::count_edges`]: crate::document::Document::count_edges
+	EdgeCountMismatch,
+	/// A face has less than three vertices.
+	DegenerateFace,
+	/// A face references the same vertex more than once.
+	DuplicateFaceIndex,
+}
+
+impl WarningKind {
+	/// Kind of the error the warning is turned into if it is denied.
+	pub fn error_kind(&self) -> ErrorKind {
+		match self {
+			WarningKind::MissingMagic => ErrorKind::InvalidMagic,
+			WarningKind::EdgeCountMismatch => ErrorKind::InvalidCounts,
+			WarningKind::DegenerateFace | WarningKind::DuplicateFaceIndex => {
+				ErrorKind::InvalidFace
+			}
+		}
+	}
+}
+
+impl fmt::Display for WarningKind {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		fmt::Debug::fmt(self, f)
+	}
+}
+
+/// Warning kinds which are reported as errors instead (see
+/// [`ParserOptions::denied_warnings`](super::ParserOptions::denied_warnings)).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DeniedWarnings {
+	pub missing_magic: bool,
+	pub edge_count_mismatch: bool,
+	pub degenerate_face: bool,
+	pub duplicate_face_index: bool,
+}
+
+impl DeniedWarnings {
+	pub fn all() -> Self {
+		Self {
+			missing_magic: true,
+			edge_count_mismatch: true,
+			degenerate_face: true,
+			duplicate_face_index: true,
+		}
+	}
+
+	pub fn contains(&self, kind: WarningKind) -> bool {
+		match kind {
+			WarningKind::MissingMagic => self.missing_magic,
+			WarningKind::EdgeCountMismatch => self.edge_count_mismatch,
+			WarningKind::DegenerateFace => self.degenerate_face,
+			WarningKind::DuplicateFaceIndex => self.duplicate_face_index,
+		}
+	}
+}
+
+/// Non-fatal problem found while parsing.
+///
+/// The location of the warning is given the same way as for an [`Error`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Warning {
+	kind: WarningKind,
+	/// Error the warning is turned into if it is denied
+	error: Error,
+}
+
+impl Warning {
+	pub(crate) fn new(kind: WarningKind, error: Error) -> Self {
+		debug_assert_eq!(kind.error_kind(), error.kind);
+
+		Self { kind, error }
+	}
+
+	pub fn kind(&self) -> WarningKind {
+		self.kind
+	}
+
+	pub fn line_index(&self) -> usize {
+		self.error.line_index
+	}
+
+	pub fn message(&self) -> Option<&str> {
+		self.error.message()
+	}
+
+	pub fn span(&self) -> Option<Range<usize>> {
+		self.error.span()
+	}
+
+	pub fn column(&self) -> Option<usize> {
+		self.error.column
+	}
+
+	pub fn hint(&self) -> Option<&str> {
+		self.error.hint()
+	}
+
+	/// Turns the warning into the error which is returned if its kind is
+	/// denied.
+	pub fn into_error(self) -> Error {
+		self.error
+	}
+}
+
+impl fmt::Display for Warning {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{} @ ln:{}", self.kind, self.line_index() + 1)?;
+
+		if let Some(column) = self.column() {
+			write!(f, ":{}", column + 1)?;
+		}
+
+		if let Some(msg) = self.message() {
+			write!(f, " - {}", msg)?;
+		}
+
+		Ok(())
+	}
+}
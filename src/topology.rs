@@ -0,0 +1,230 @@
+use std::fmt;
+
+use crate::adjacency::Adjacency;
+use crate::document::{Checked, Document};
+use crate::vertex::VertexRef;
+
+/// Topological problems of a document (see [`Document::topology`]).
+///
+/// Edges are given by their vertices (smaller index first), faces by their
+/// index in the document. All lists are sorted.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct TopologyReport {
+	/// Edges shared by more than two faces, together with these faces.
+	pub non_manifold_edges: Vec<((VertexRef, VertexRef), Vec<usize>)>,
+	/// Vertices at which several separate fans of faces meet (e.g. the tip
+	/// of two cones).
+	pub non_manifold_vertices: Vec<VertexRef>,
+	/// Edges of only one face, together with that face.
+	pub boundary_edges: Vec<((VertexRef, VertexRef), usize)>,
+	/// Vertices which are not part of any face.
+	pub isolated_vertices: Vec<VertexRef>,
+}
+
+impl TopologyReport {
+	pub fn new(document: &Document<Checked>) -> Self {
+		let adjacency = Adjacency::new(document);
+		let mut report = Self::default();
+
+		for ((a, b), faces) in adjacency.edges() {
+			match faces {
+				[face] => report.boundary_edges.push(((a, b), *face)),
+				[_, _] => {}
+				_ => report.non_manifold_edges.push(((a, b), faces.to_vec())),
+			}
+		}
+
+		for vertex in (0..document.vertices.len()).map(VertexRef) {
+			if adjacency.vertex_faces(vertex).is_empty() {
+				report.isolated_vertices.push(vertex);
+			} else if fan_count(&adjacency, vertex) > 1 {
+				report.non_manifold_vertices.push(vertex);
+			}
+		}
+
+		report
+	}
+
+	/// Neither edges nor vertices are non-manifold.
+	pub fn is_manifold(&self) -> bool {
+		self.non_manifold_edges.is_empty()
+			&& self.non_manifold_vertices.is_empty()
+	}
+
+	/// There are no boundary edges.
+	pub fn is_closed(&self) -> bool {
+		self.boundary_edges.is_empty()
+	}
+
+	/// No problems were found at all.
+	pub fn is_empty(&self) -> bool {
+		self.is_manifold()
+			&& self.is_closed()
+			&& self.isolated_vertices.is_empty()
+	}
+}
+
+/// Number of groups of faces around a vertex which are connected by the
+/// edges of the vertex.
+fn fan_count(adjacency: &Adjacency, vertex: VertexRef) -> usize {
+	let faces = adjacency.vertex_faces(vertex);
+	// Union-find over the positions in `faces`
+	let mut parents = (0..faces.len()).collect::<Vec<_>>();
+
+	fn root(parents: &mut [usize], mut i: usize) -> usize {
+		while parents[i] != i {
+			parents[i] = parents[parents[i]];
+			i = parents[i];
+		}
+
+		i
+	}
+
+	for &neighbour in adjacency.vertex_neighbours(vertex) {
+		let mut edge_faces = adjacency
+			.edge_faces(vertex, neighbour)
+			.iter()
+			// Both lists are sorted and the edge faces are a subset
+			.filter_map(|face| faces.binary_search(face).ok());
+
+		if let Some(first) = edge_faces.next() {
+			for other in edge_faces {
+				let (a, b) =
+					(root(&mut parents, first), root(&mut parents, other));
+				parents[a] = b;
+			}
+		}
+	}
+
+	(0..faces.len()).filter(|&i| root(&mut parents, i) == i).count()
+}
+
+impl fmt::Display for TopologyReport {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let edges = |edges: Vec<(VertexRef, VertexRef)>| {
+			list(edges.iter().map(|(a, b)| format!("{}-{}", a.0, b.0)))
+		};
+		let vertices = |vertices: &[VertexRef]| {
+			list(vertices.iter().map(|vertex| vertex.0.to_string()))
+		};
+
+		let problems = [
+			(
+				"non-manifold edges",
+				self.non_manifold_edges.len(),
+				edges(
+					self.non_manifold_edges.iter().map(|(e, _)| *e).collect(),
+				),
+			),
+			(
+				"non-manifold vertices",
+				self.non_manifold_vertices.len(),
+				vertices(&self.non_manifold_vertices),
+			),
+			(
+				"boundary edges",
+				self.boundary_edges.len(),
+				edges(self.boundary_edges.iter().map(|(e, _)| *e).collect()),
+			),
+			(
+				"isolated vertices",
+				self.isolated_vertices.len(),
+				vertices(&self.isolated_vertices),
+			),
+		];
+
+		let mut problems =
+			problems.iter().filter(|(_, count, _)| *count > 0).peekable();
+
+		if problems.peek().is_none() {
+			return write!(f, "No topological problems");
+		}
+
+		for (i, (name, count, items)) in problems.enumerate() {
+			if i > 0 {
+				write!(f, "; ")?;
+			}
+
+			write!(f, "{} {} ({})", count, name, items)?;
+		}
+
+		Ok(())
+	}
+}
+
+/// Joins the first few items of a (possibly long) list.
+fn list(items: impl ExactSizeIterator<Item = String>) -> String {
+	const SHOWN: usize = 8;
+
+	let count = items.len();
+	let mut shown = items.take(SHOWN).collect::<Vec<_>>();
+
+	if count > SHOWN {
+		shown.push("...".into());
+	}
+
+	shown.join(", ")
+}
+
+impl Document<Checked> {
+	/// Checks the topology of the faces (see [`TopologyReport`]).
+	pub fn topology(&self) -> TopologyReport {
+		TopologyReport::new(self)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::document::ValidationOptions;
+	use crate::parse::OffParser;
+
+	#[test]
+	fn closed() {
+		let content = include_str!("../resources/wiki.off");
+		let document = OffParser::new(&content).try_parse().unwrap();
+
+		let options = ValidationOptions {
+			manifold: true,
+			closed: true,
+			isolated_vertices: true,
+			..Default::default()
+		};
+		let report =
+			document.validate_with_options(options).unwrap().topology();
+
+		assert!(report.is_empty());
+		assert_eq!(report.to_string(), "No topological problems");
+	}
+
+	#[test]
+	fn problems() {
+		// Square (faces 0 and 1) with two fins on its edge 0-1 (faces 2 and
+		// 3), a triangle touching it at vertex 2 (face 4) and the unused
+		// vertex 8
+		let content = "OFF\n9 5\n0 0 0\n1 0 0\n1 1 0\n0 1 0\n0 0 1\n2 2 0\n3 \
+		               2 0\n0 0 -1\n9 9 9\n3 0 1 2\n3 0 2 3\n3 1 0 4\n3 0 1 \
+		               7\n3 2 5 6\n";
+		let document = OffParser::new(&content).try_parse().unwrap();
+
+		let report = document.clone().validate().unwrap().topology();
+
+		assert_eq!(
+			report.non_manifold_edges,
+			[((VertexRef(0), VertexRef(1)), vec![0, 2, 3])]
+		);
+		assert_eq!(report.non_manifold_vertices, [VertexRef(2)]);
+		assert_eq!(report.boundary_edges.len(), 10);
+		assert_eq!(report.isolated_vertices, [VertexRef(8)]);
+		assert!(!report.is_manifold());
+		assert!(!report.is_closed());
+
+		let err = document
+			.validate_with_options(ValidationOptions {
+				manifold: true,
+				..Default::default()
+			})
+			.unwrap_err();
+		assert!(err.to_string().contains("1 non-manifold vertices (2)"));
+	}
+}
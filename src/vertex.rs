@@ -9,13 +9,152 @@ impl Vertex {
 	pub fn new(x: f32, y: f32, z: f32) -> Self {
 		Self { x, y, z }
 	}
+
+	/// Vertex of the first three coordinates, missing ones are `0.0`.
+	pub fn from_coordinates(coordinates: &[f32]) -> Self {
+		let coordinate = |i: usize| coordinates.get(i).copied();
+
+		Self::new(
+			coordinate(0).unwrap_or_default(),
+			coordinate(1).unwrap_or_default(),
+			coordinate(2).unwrap_or_default(),
+		)
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Normal {
+	pub x: f32,
+	pub y: f32,
+	pub z: f32,
+}
+
+impl Normal {
+	pub fn new(x: f32, y: f32, z: f32) -> Self {
+		Self { x, y, z }
+	}
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct TextureCoord {
+	pub s: f32,
+	pub t: f32,
+}
+
+impl TextureCoord {
+	pub fn new(s: f32, t: f32) -> Self {
+		Self { s, t }
+	}
+}
+
+/// Stores vertices of an arbitrary (but fixed) dimension.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VertexStore {
+	dimension: usize,
+	coordinates: Vec<f32>,
+}
+
+impl VertexStore {
+	/// # Panics
+	///
+	/// If `dimension` is `0`.
+	pub fn new(dimension: usize) -> Self {
+		Self::with_capacity(dimension, 0)
+	}
+
+	/// # Panics
+	///
+	/// If `dimension` is `0`.
+	pub fn with_capacity(dimension: usize, vertex_count: usize) -> Self {
+		assert!(dimension > 0, "Dimension must not be 0");
+
+		Self {
+			dimension,
+			coordinates: Vec::with_capacity(dimension * vertex_count),
+		}
+	}
+
+	pub fn dimension(&self) -> usize {
+		self.dimension
+	}
+
+	pub fn len(&self) -> usize {
+		self.coordinates.len() / self.dimension
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.coordinates.is_empty()
+	}
+
+	/// Returns all coordinates of the vertex at `index`.
+	pub fn get(&self, index: usize) -> Option<&[f32]> {
+		let start = index.checked_mul(self.dimension)?;
+		self.coordinates.get(start..start.checked_add(self.dimension)?)
+	}
+
+	pub fn get_mut(&mut self, index: usize) -> Option<&mut [f32]> {
+		let start = index.checked_mul(self.dimension)?;
+		self.coordinates.get_mut(start..start.checked_add(self.dimension)?)
+	}
+
+	pub fn iter(&self) -> std::slice::ChunksExact<'_, f32> {
+		self.coordinates.chunks_exact(self.dimension)
+	}
+
+	/// Coordinates of all vertices, one vertex after another.
+	pub fn as_flat_slice(&self) -> &[f32] {
+		&self.coordinates
+	}
+
+	/// # Panics
+	///
+	/// If the number of coordinates does not match the dimension.
+	pub fn push(&mut self, coordinates: &[f32]) {
+		assert_eq!(
+			coordinates.len(),
+			self.dimension,
+			"Number of coordinates does not match dimension"
+		);
+
+		self.coordinates.extend_from_slice(coordinates);
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct VertexRef(pub(crate) usize);
 
 impl VertexRef {
-	pub(crate) unsafe fn resolve_unchecked(self, items: &[Vertex]) -> &Vertex {
+	/// Index of the referenced vertex.
+	pub fn index(self) -> usize {
+		self.0
+	}
+
+	pub(crate) unsafe fn resolve_unchecked<T>(self, items: &[T]) -> &T {
 		items.get_unchecked(self.0)
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn store() {
+		let mut store = VertexStore::new(2);
+		store.push(&[1.0, 2.0]);
+		store.push(&[3.0, 4.0]);
+
+		assert_eq!(store.len(), 2);
+		assert_eq!(store.get(1), Some(&[3.0, 4.0][..]));
+		assert_eq!(store.get(2), None);
+		assert_eq!(store.get(usize::MAX / 2), None);
+		assert_eq!(store.get_mut(usize::MAX), None);
+
+		let vertices =
+			store.iter().map(Vertex::from_coordinates).collect::<Vec<_>>();
+		assert_eq!(
+			vertices,
+			[Vertex::new(1.0, 2.0, 0.0), Vertex::new(3.0, 4.0, 0.0)]
+		);
+	}
+}
@@ -0,0 +1,337 @@
+pub mod binary;
+
+use std::io;
+
+use crate::color::Color;
+use crate::colorformat::ColorFormat;
+use crate::document::Document;
+use crate::header::OffHeader;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WriterOptions<C> {
+	pub color_format: C,
+	/// Number of decimal places written for coordinates, normals and
+	/// texture coordinates. If not set the
+	/// shortest representation which parses back to the same value is used.
+	pub precision: Option<usize>,
+}
+
+impl Default for WriterOptions<crate::colorformat::Any> {
+	fn default() -> Self {
+		Self { color_format: crate::colorformat::Any, precision: None }
+	}
+}
+
+/// Writes documents in the text format.
+///
+/// Documents with colormap indices do not round-trip with the defaults:
+/// [`OffWriter::new`] writes the indices, but
+/// [`OffParser::new`](crate::parse::OffParser::new) rejects them. Such output
+/// has to be parsed with the color format
+/// [`Indexed`](crate::colorformat::Indexed).
+pub struct OffWriter<'a, S, C> {
+	options: WriterOptions<C>,
+	document: &'a Document<S>,
+}
+
+impl<'a, S> OffWriter<'a, S, crate::colorformat::Any> {
+	pub fn new(document: &'a Document<S>) -> Self {
+		Self { document, options: Default::default() }
+	}
+}
+
+impl<'a, S, C> OffWriter<'a, S, C>
+where
+	C: ColorFormat,
+{
+	pub fn new_with_options(
+		document: &'a Document<S>,
+		options: WriterOptions<C>,
+	) -> Self {
+		Self { document, options }
+	}
+
+	/// Colormap indices of faces are written as a single integer, which only
+	/// parses back with the color format
+	/// [`Indexed`](crate::colorformat::Indexed). Like all colors they are
+	/// dropped for color formats without elements.
+	///
+	/// # Panics
+	///
+	/// If the number of a vertex attribute does not match the number of
+	/// vertices. This can only happen for unchecked documents.
+	pub fn try_write<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+		self.try_write_magic(&mut writer)?;
+		self.try_write_counts(&mut writer)?;
+
+		let mut encoder = TextEncoder {
+			writer: &mut writer,
+			options: &self.options,
+			line_start: true,
+		};
+		try_write_vertices(self.document, self.vertex_colors(), &mut encoder)?;
+
+		for face_ref in &self.document.face_refs {
+			write!(writer, "{}", face_ref.vertex_refs.len())?;
+
+			for vertex_ref in &face_ref.vertex_refs {
+				write!(writer, " {}", vertex_ref.0)?;
+			}
+
+			if let Some(color) = &face_ref.color {
+				C::try_write(color, &mut writer)?;
+			} else if let Some(index) =
+				face_ref.colormap_index.filter(|_| C::HAS_ELEMENTS)
+			{
+				write!(writer, " {}", index)?;
+			}
+
+			writeln!(writer)?;
+		}
+
+		writer.flush()
+	}
+
+	pub fn write_string(&self) -> String {
+		let mut buf = Vec::new();
+
+		self.try_write(&mut buf).expect("Writing to a `Vec` failed");
+
+		String::from_utf8(buf).expect("Writer produced invalid utf-8")
+	}
+
+	/// Vertex colors which will be written. They are dropped if the color
+	/// format can not represent them.
+	fn vertex_colors(&self) -> Option<&[Color]> {
+		self.document.vertex_colors.as_deref().filter(|_| C::HAS_ELEMENTS)
+	}
+
+	fn try_write_magic(&self, writer: &mut impl io::Write) -> io::Result<()> {
+		let header = OffHeader {
+			vertex_colors: self.vertex_colors().is_some(),
+			..self.document.layout()
+		};
+
+		writeln!(writer, "{}", header)?;
+
+		if header.n_dimensional {
+			writeln!(writer, "{}", header.dimension)?;
+		}
+
+		Ok(())
+	}
+
+	fn try_write_counts(&self, writer: &mut impl io::Write) -> io::Result<()> {
+		let vertex_count = self.document.vertices.len();
+		let face_count = self.document.face_refs.len();
+
+		// An absent edge count is omitted instead of written as `0` so that
+		// the output parses back to an equal document.
+		if let Some(edge_count) = self.document.edge_count {
+			writeln!(writer, "{} {} {}", vertex_count, face_count, edge_count)
+		} else {
+			writeln!(writer, "{} {}", vertex_count, face_count)
+		}
+	}
+}
+
+/// Encoding of the elements of a vertex. The text and the binary writer only
+/// differ in the encoding, the attributes are written by
+/// [`try_write_vertices`] for both.
+pub(crate) trait VertexEncoder {
+	/// Writes a group of elements (e.g. the coordinates or the normal).
+	fn try_write_floats(&mut self, values: &[f32]) -> io::Result<()>;
+
+	fn try_write_color(&mut self, color: &Color) -> io::Result<()>;
+
+	fn try_end_vertex(&mut self) -> io::Result<()> {
+		Ok(())
+	}
+}
+
+/// Writes all vertices of `document` with their attributes. `colors` are
+/// the vertex colors which can be represented by the encoder.
+///
+/// Panics if the number of a vertex attribute does not match the number of
+/// vertices.
+pub(crate) fn try_write_vertices<S>(
+	document: &Document<S>,
+	colors: Option<&[Color]>,
+	encoder: &mut impl VertexEncoder,
+) -> io::Result<()> {
+	for (index, vertex) in document.vertices.iter().enumerate() {
+		if let Some(coordinates) = &document.coordinates {
+			let coordinates = coordinates
+				.get(index)
+				.expect("Number of coordinates does not match vertices");
+			encoder.try_write_floats(coordinates)?;
+		} else {
+			encoder.try_write_floats(&[vertex.x, vertex.y, vertex.z])?;
+		}
+
+		if let Some(w) = &document.vertex_w {
+			encoder.try_write_floats(&[w[index]])?;
+		}
+
+		if let Some(normals) = &document.vertex_normals {
+			let normal = &normals[index];
+			encoder.try_write_floats(&[normal.x, normal.y, normal.z])?;
+		}
+
+		if let Some(colors) = colors {
+			encoder.try_write_color(&colors[index])?;
+		}
+
+		if let Some(texture_coords) = &document.texture_coords {
+			let texture_coord = &texture_coords[index];
+			encoder.try_write_floats(&[texture_coord.s, texture_coord.t])?;
+		}
+
+		encoder.try_end_vertex()?;
+	}
+
+	Ok(())
+}
+
+/// Writes each vertex as a line of space separated elements.
+struct TextEncoder<'o, W, C> {
+	writer: W,
+	options: &'o WriterOptions<C>,
+	/// Whether nothing was written for the current vertex yet
+	line_start: bool,
+}
+
+impl<W: io::Write, C: ColorFormat> VertexEncoder for TextEncoder<'_, W, C> {
+	/// Writes the floats with the configured precision.
+	fn try_write_floats(&mut self, values: &[f32]) -> io::Result<()> {
+		for value in values {
+			if !self.line_start {
+				write!(self.writer, " ")?;
+			}
+
+			self.line_start = false;
+
+			if let Some(precision) = self.options.precision {
+				write!(self.writer, "{:.*}", precision, value)?;
+			} else {
+				write!(self.writer, "{}", value)?;
+			}
+		}
+
+		Ok(())
+	}
+
+	fn try_write_color(&mut self, color: &Color) -> io::Result<()> {
+		C::try_write(color, &mut self.writer)
+	}
+
+	fn try_end_vertex(&mut self) -> io::Result<()> {
+		self.line_start = true;
+		writeln!(self.writer)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::colorformat::{Indexed, RgbU8, RgbaF32};
+	use crate::parse::{OffParser, ParserOptions};
+
+	#[test]
+	fn roundtrip_resources() {
+		for res in std::fs::read_dir("resources").unwrap() {
+			let res = res.expect("Failed to get resources");
+			let content = std::fs::read_to_string(res.path())
+				.expect("Failed to read resource");
+
+			let document = OffParser::new(&content).try_parse().unwrap();
+			let written = OffWriter::new(&document).write_string();
+			let reparsed = OffParser::new(&written).try_parse().unwrap();
+
+			assert_eq!(document, reparsed, "{}", res.path().display());
+		}
+	}
+
+	#[test]
+	fn roundtrip_color_formats() {
+		let content = include_str!("../resources/prinston.off");
+
+		let document = OffParser::new(&content).try_parse().unwrap();
+
+		let options = WriterOptions { color_format: RgbaF32, precision: None };
+		let written =
+			OffWriter::new_with_options(&document, options).write_string();
+		let options = ParserOptions::with_color_format(RgbaF32);
+		let reparsed = OffParser::new_with_options(&written, options)
+			.try_parse()
+			.unwrap();
+
+		assert_eq!(document, reparsed);
+
+		let options =
+			WriterOptions { color_format: RgbU8, precision: Some(2) };
+		let written =
+			OffWriter::new_with_options(&document, options).write_string();
+
+		assert!(written.starts_with("OFF\n8 6 12\n1.63 0.00 1.15\n"));
+		assert!(written.contains("\n4 0 1 2 3 255 0 0\n"));
+	}
+
+	#[test]
+	fn roundtrip_vertex_attributes() {
+		let content = "STCNOFF\n3 1\n0 0 0 0 0 1 255 0 0 255 0 0\n1 0 0 0 0 \
+		               1 0 255 0 128 1 0\n0 1 0 0 0 1 0 0 255 0 0 1\n3 0 1 \
+		               2\n";
+
+		let document = OffParser::new(&content).try_parse().unwrap();
+		assert!(document.vertex_colors().is_some());
+
+		let written = OffWriter::new(&document).write_string();
+		assert_eq!(written, content);
+
+		let options = WriterOptions {
+			color_format: crate::colorformat::None,
+			precision: None,
+		};
+		let written =
+			OffWriter::new_with_options(&document, options).write_string();
+		assert!(written.starts_with("STNOFF\n3 1\n0 0 0 0 0 1 0 0\n"));
+	}
+
+	#[test]
+	fn colormap_indices() {
+		let content = "OFF\n3 1\n0 0 0\n1 0 0\n0 1 0\n3 0 1 2 1\n";
+		let options = ParserOptions::with_color_format(Indexed(RgbU8));
+		let document = OffParser::new_with_options(&content, options)
+			.try_parse()
+			.unwrap();
+
+		let written = OffWriter::new_with_options(
+			&document,
+			WriterOptions { color_format: Indexed(RgbU8), precision: None },
+		)
+		.write_string();
+		assert_eq!(written, content);
+
+		// Only `Indexed` parses the indices back
+		let written = OffWriter::new(&document).write_string();
+		assert_eq!(written, content);
+		assert!(OffParser::new(&written).try_parse().is_err());
+
+		let written = OffWriter::new_with_options(
+			&document,
+			WriterOptions {
+				color_format: crate::colorformat::None,
+				precision: None,
+			},
+		)
+		.write_string();
+		assert!(written.ends_with("\n3 0 1 2\n"));
+		assert!(OffParser::new_with_options(
+			&written,
+			ParserOptions::with_color_format(crate::colorformat::None)
+		)
+		.try_parse()
+		.is_ok());
+	}
+}
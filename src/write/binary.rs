@@ -0,0 +1,210 @@
+use std::io;
+
+use crate::color::Color;
+use crate::document::Document;
+use crate::header::OffHeader;
+use crate::write::{try_write_vertices, VertexEncoder};
+
+/// Writer for the binary variant of the OFF format (`OFF BINARY`).
+///
+/// Vertex and face colors are always written as four float elements (rgba).
+/// Face colors are preceded by their element count. Faces without a color
+/// have an element count of `0`, colormap indices have an element count of
+/// `1`.
+///
+/// As binary documents always contain an edge count, an absent one is written
+/// as `0`.
+pub struct BinaryOffWriter<'a, S> {
+	document: &'a Document<S>,
+}
+
+impl<'a, S> BinaryOffWriter<'a, S> {
+	pub fn new(document: &'a Document<S>) -> Self {
+		Self { document }
+	}
+
+	/// # Panics
+	///
+	/// Same as [`OffWriter::try_write`](super::OffWriter::try_write).
+	pub fn try_write<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+		let header = OffHeader { binary: true, ..self.document.layout() };
+
+		writeln!(writer, "{}", header)?;
+
+		if header.n_dimensional {
+			try_write_count(&mut writer, header.dimension)?;
+		}
+
+		let edge_count = self.document.edge_count.unwrap_or(0);
+
+		try_write_count(&mut writer, self.document.vertices.len())?;
+		try_write_count(&mut writer, self.document.face_refs.len())?;
+		try_write_count(&mut writer, edge_count)?;
+
+		try_write_vertices(
+			self.document,
+			self.document.vertex_colors.as_deref(),
+			&mut BinaryEncoder(&mut writer),
+		)?;
+
+		for face_ref in &self.document.face_refs {
+			try_write_count(&mut writer, face_ref.vertex_refs.len())?;
+
+			for vertex_ref in &face_ref.vertex_refs {
+				try_write_count(&mut writer, vertex_ref.0)?;
+			}
+
+			if let Some(color) = &face_ref.color {
+				try_write_count(&mut writer, 4)?;
+				try_write_rgba(&mut writer, color)?;
+			} else if let Some(index) = face_ref.colormap_index {
+				try_write_count(&mut writer, 1)?;
+				try_write_count(&mut writer, index)?;
+			} else {
+				try_write_count(&mut writer, 0)?;
+			}
+		}
+
+		writer.flush()
+	}
+
+	pub fn write_bytes(&self) -> Vec<u8> {
+		let mut buf = Vec::new();
+
+		self.try_write(&mut buf).expect("Writing to a `Vec` failed");
+
+		buf
+	}
+}
+
+/// Writes all elements as big-endian floats.
+struct BinaryEncoder<W>(W);
+
+impl<W: io::Write> VertexEncoder for BinaryEncoder<W> {
+	fn try_write_floats(&mut self, values: &[f32]) -> io::Result<()> {
+		try_write_floats(&mut self.0, values)
+	}
+
+	fn try_write_color(&mut self, color: &Color) -> io::Result<()> {
+		try_write_rgba(&mut self.0, color)
+	}
+}
+
+fn try_write_rgba(
+	writer: &mut impl io::Write,
+	color: &Color,
+) -> io::Result<()> {
+	try_write_floats(writer, &color.to_f32())
+}
+
+fn try_write_floats(
+	writer: &mut impl io::Write,
+	values: &[f32],
+) -> io::Result<()> {
+	for value in values {
+		writer.write_all(&value.to_be_bytes())?;
+	}
+
+	Ok(())
+}
+
+fn try_write_count<T: TryInto<i32>>(
+	writer: &mut impl io::Write,
+	count: T,
+) -> io::Result<()> {
+	let count = count.try_into().map_err(|_| {
+		io::Error::new(
+			io::ErrorKind::InvalidData,
+			"Count does not fit into a 32-bit integer",
+		)
+	})?;
+
+	writer.write_all(&count.to_be_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::colorformat::RgbaF32;
+	use crate::parse::binary::BinaryOffParser;
+	use crate::parse::{OffParser, ParserOptions};
+	use crate::write::{OffWriter, WriterOptions};
+
+	#[test]
+	fn roundtrip_against_text() {
+		for res in std::fs::read_dir("resources").unwrap() {
+			let res = res.expect("Failed to get resources");
+			let content = std::fs::read_to_string(res.path())
+				.expect("Failed to read resource");
+
+			let document = OffParser::new(&content).try_parse().unwrap();
+
+			let options =
+				WriterOptions { color_format: RgbaF32, precision: None };
+			let text =
+				OffWriter::new_with_options(&document, options).write_string();
+			let options = ParserOptions::with_color_format(RgbaF32);
+			let from_text = OffParser::new_with_options(&text, options)
+				.try_parse()
+				.unwrap();
+
+			let bytes = BinaryOffWriter::new(&document).write_bytes();
+			let from_binary =
+				BinaryOffParser::new(&bytes).try_parse().unwrap();
+
+			assert_eq!(from_text, from_binary, "{}", res.path().display());
+			assert_eq!(document, from_binary, "{}", res.path().display());
+		}
+	}
+
+	#[test]
+	fn roundtrip_vertex_attributes() {
+		let content = "STCNOFF\n3 1 3\n0 0 0 0 0 1 255 0 0 255 0 0\n1 0 0 0 \
+		               0 1 0 255 0 128 1 0\n0 1 0 0 0 1 0 0 255 0 0 1\n3 0 1 \
+		               2 10 20 30\n";
+
+		let document = OffParser::new(&content).try_parse().unwrap();
+
+		let bytes = BinaryOffWriter::new(&document).write_bytes();
+		assert!(bytes.starts_with(b"STCNOFF BINARY\n"));
+
+		let from_binary = BinaryOffParser::new(&bytes).try_parse().unwrap();
+		assert_eq!(
+			*from_binary.header(),
+			OffHeader { binary: true, ..*document.header() }
+		);
+		assert_eq!(document, from_binary);
+	}
+
+	#[test]
+	fn roundtrip_homogeneous() {
+		let content = "4OFF\n2 0 0\n2 4 6 2\n1 1 1 0.5\n";
+
+		let document = OffParser::new(&content).try_parse().unwrap();
+		assert_eq!(OffWriter::new(&document).write_string(), content);
+
+		let bytes = BinaryOffWriter::new(&document).write_bytes();
+		let from_binary = BinaryOffParser::new(&bytes).try_parse().unwrap();
+		assert_eq!(
+			*from_binary.header(),
+			OffHeader { binary: true, ..*document.header() }
+		);
+		assert_eq!(document, from_binary);
+	}
+
+	#[test]
+	fn roundtrip_n_dimensional() {
+		let content = "4nOFF\n5\n2 1 1\n1 2 3 4 5 1\n6 7 8 9 10 2\n2 0 1\n";
+
+		let document = OffParser::new(&content).try_parse().unwrap();
+		assert_eq!(OffWriter::new(&document).write_string(), content);
+
+		let bytes = BinaryOffWriter::new(&document).write_bytes();
+		let from_binary = BinaryOffParser::new(&bytes).try_parse().unwrap();
+		assert_eq!(
+			*from_binary.header(),
+			OffHeader { binary: true, ..*document.header() }
+		);
+		assert_eq!(document, from_binary);
+	}
+}